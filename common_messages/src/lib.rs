@@ -9,4 +9,8 @@
 // Example written by Robert Hannah 2025
 // -------------------------------------------------------------------------------------------------
 
+// Lets code generated by #[derive(HyperionMessage)] name this crate from inside it
+extern crate self as common_messages;
+
 pub mod messages;
+pub mod routing;
@@ -0,0 +1,32 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Package
+use serde::{Serialize, Deserialize};
+
+
+/// Acknowledges a [`Reliable`](crate::messages::reliable::Reliable) message, stopping its retransmission
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Ack {
+    /// Id of the acknowledged message
+    pub message_id: u64,
+    /// Container name of the acknowledging container
+    pub sender: String
+}
+
+impl Ack {
+    pub fn new(message_id: u64, sender: String) -> Ack {
+        Ack {
+            message_id,
+            sender
+        }
+    }
+}
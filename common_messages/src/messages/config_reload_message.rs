@@ -0,0 +1,31 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Package
+use serde::{Serialize, Deserialize};
+
+
+/// Asks the component to re-read its configuration, e.g. after the service manager sent SIGHUP
+///
+/// Only accepted from within the container: received from another container it decodes as an UnknownMessage.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigReloadMessage {
+    /// Path of the container configuration file to read
+    pub config_path: String
+}
+
+impl ConfigReloadMessage {
+    pub fn new(config_path: String) -> ConfigReloadMessage {
+        ConfigReloadMessage {
+            config_path
+        }
+    }
+}
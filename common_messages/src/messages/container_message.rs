@@ -12,34 +12,568 @@
 // Package
 use hyperion_framework::messages::container_directive::ContainerDirective;
 use hyperion_framework::messages::component_directive::ComponentDirective;
-use hyperion_framework::containerisation::traits::HyperionContainerDirectiveMessage;
-use serde::{Serialize, Deserialize};
+use hyperion_message_derive::HyperionMessage;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde_json::Value;
 
 // Local
 use crate::messages::example_message::ExampleMessage;
 use crate::messages::another_example_message::AnotherExampleMessage;
+use crate::messages::forward_message::ForwardMessage;
+use crate::messages::config_reload_message::ConfigReloadMessage;
+use crate::messages::envelope::Envelope;
+use crate::messages::error_message::ErrorMessage;
+use crate::messages::reliable::Reliable;
+use crate::messages::ack::Ack;
+use crate::messages::signed::{MessageAuth, Signed, SIGNED_KIND};
+use crate::messages::subscribe::Subscribe;
+use crate::messages::heartbeat::Heartbeat;
+use crate::messages::peer_status::PeerStatus;
+use crate::messages::traced::Traced;
+use crate::messages::protocol_hello::{is_supported_version, ProtocolHello, PROTOCOL_VERSION, MIN_SUPPORTED_PROTOCOL_VERSION};
+use crate::messages::unknown_message::UnknownMessage;
+use crate::routing::peer_identity::is_verified_peer;
 
 // This will be the only message that is sent between containers
 // Container and component directives are essential for the Hyperion Network
 // The rest are specific to your project
+//
+// On the wire every message carries the protocol version of its sender:
+//     {"protocol_version": 1, "type": "ExampleMessage", "payload": {"message": "x", "value": 41}}
+// Decoding never fails. Anything this build doesn't understand (a variant or field added by a newer
+// container, or a protocol version that is no longer supported) becomes an UnknownMessage, so
+// individual containers can be upgraded one at a time. Give new message fields #[serde(default)]
+// so older containers' messages still decode.
+// Containers built before versioning only decode the unversioned format, which is never sent, so the
+// first upgrade to a versioned build must include every container.
+//
+// With authentication installed (see messages::signed) every message is sent as a "Signed" message
+// wrapping the wire form above, and received messages that aren't validly signed become UnknownMessages.
+// Messages are only signed where they leave the container (see signed_by), never while serializing.
+//
+// Messages marked #[hyperion(local)] (e.g. ConfigReloadMessage, which names a file to read, ForwardMessage, which
+// would make the receiver relay for the sender, or PeerStatus, which the peer monitor raises) only come from within
+// the container, like its console or runtime. Received from another container, they become UnknownMessages.
+//
+// HyperionMessage generates the directive accessor, From/TryFrom conversions for every message and the
+// per-variant parts of the wire format, so adding a message only needs a new variant below.
 
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, HyperionMessage)]
 pub enum ContainerMessage {
+    #[hyperion(directive)]
     ContainerDirectiveMsg       (ContainerDirective),
     ComponentDirectiveMsg       (ComponentDirective),
     ExampleMessage              (ExampleMessage),
     AnotherExampleMessage       (AnotherExampleMessage),
+    #[hyperion(local)]
+    ForwardMessage              (ForwardMessage),
+    #[hyperion(local)]
+    ConfigReloadMessage         (ConfigReloadMessage),
+    Envelope                    (Envelope),
+    ProtocolHello               (ProtocolHello),
+    ErrorMessage                (ErrorMessage),
+    Reliable                    (Reliable),
+    Ack                         (Ack),
+    Subscribe                   (Subscribe),
+    Heartbeat                   (Heartbeat),
+    #[hyperion(local)]
+    PeerStatus                  (PeerStatus),
+    Traced                      (Traced),
+    #[hyperion(signed)]
+    Signed                      (Signed),
+    #[hyperion(unknown)]
+    UnknownMessage              (UnknownMessage),
     // Add more messages as needed
 }
 
-impl HyperionContainerDirectiveMessage for ContainerMessage {
-    // Gets ContainerDirective if is instance
-    fn get_container_directive_message(&self) -> Option<&ContainerDirective> {
+impl ContainerMessage {
+    /// Decodes a message from its wire representation, never failing
+    ///
+    /// Besides the versioned format, the unversioned format used before versioning (`{"ExampleMessage": {..}}`)
+    /// is understood as protocol version 0. A versioned message without a protocol version is assumed to use
+    /// this build's version, which keeps hand-written messages (e.g. from the console) short.
+    ///
+    /// With authentication installed, only validly signed messages are decoded, see [`crate::messages::signed`].
+    /// Local only messages are rejected, wherever they are nested.
+    pub fn from_wire(value: Value) -> ContainerMessage {
+        let message = match (MessageAuth::installed(), ContainerMessage::decode_wire(value)) {
+            (Some(auth), message) => ContainerMessage::open_signed(auth, message),
+            (None, ContainerMessage::Signed(signed)) => ContainerMessage::UnknownMessage(UnknownMessage {
+                kind: SIGNED_KIND.to_string(),
+                protocol_version: PROTOCOL_VERSION,
+                payload: serde_json::to_value(&signed).unwrap_or(Value::Null),
+                reason: "signed messages are only accepted with authentication enabled".to_string()
+            }),
+            (None, message) => message
+        };
+        if !message.contains_local_only() {
+            return message;
+        }
+        ContainerMessage::UnknownMessage(UnknownMessage {
+            kind: message.kind().to_string(),
+            protocol_version: PROTOCOL_VERSION,
+            payload: serde_json::to_value(&message).unwrap_or(Value::Null),
+            reason: "the message is only accepted from within the container".to_string()
+        })
+    }
+
+    /// Decodes a message that didn't come from another container, e.g. one typed on the console or stored by
+    /// this container, with the same versioning rules as [`ContainerMessage::from_wire`] but no further checks
+    pub fn from_local(value: Value) -> ContainerMessage {
+        ContainerMessage::decode_wire(value)
+    }
+
+    /// Signs the message as this container for sending it to other containers
+    ///
+    /// Already signed messages are returned as they are.
+    pub fn signed_by(self, auth: &MessageAuth) -> ContainerMessage {
+        if let ContainerMessage::Signed(_) = self {
+            return self;
+        }
+        // Every map key of a ContainerMessage is a string, so serializing it to JSON can't fail
+        let mut signed = auth.sign(serde_json::to_string(&self).expect("a ContainerMessage serializes to JSON"));
         if let ContainerMessage::ContainerDirectiveMsg(directive) = self {
-            Some(directive)
+            signed.directive = Some(directive);
+        }
+        ContainerMessage::Signed(signed)
+    }
+
+    /// Whether this message, or one it carries, is only accepted from within the container
+    pub fn contains_local_only(&self) -> bool {
+        self.is_local_only() || self.carried_message().is_some_and(ContainerMessage::contains_local_only)
+    }
+
+    /// Whether this is a container or component directive, or carries one for another container
+    pub fn carries_directive(&self) -> bool {
+        match self {
+            ContainerMessage::ContainerDirectiveMsg(_) | ContainerMessage::ComponentDirectiveMsg(_) => true,
+            ContainerMessage::Signed(signed) => signed.directive.is_some()
+                || serde_json::from_str(&signed.message).is_ok_and(|message| ContainerMessage::decode_wire(message).carries_directive()),
+            message => message.carried_message().is_some_and(ContainerMessage::carries_directive)
+        }
+    }
+
+    /// Whether this message is signed, or carries a signed message
+    fn contains_signed(&self) -> bool {
+        match self {
+            ContainerMessage::Signed(_) => true,
+            ContainerMessage::UnknownMessage(unknown) => unknown.kind == SIGNED_KIND,
+            message => message.carried_message().is_some_and(ContainerMessage::contains_signed)
+        }
+    }
+
+    /// A container named as the sender of this message, or of one it carries, other than `sender`
+    fn sender_other_than(&self, sender: &str) -> Option<&str> {
+        match self.claimed_sender() {
+            Some(claimed) if claimed != sender => Some(claimed),
+            _ => self.carried_message().and_then(|message| message.sender_other_than(sender))
+        }
+    }
+
+    /// The container this message says it comes from, for the messages that name their sender
+    fn claimed_sender(&self) -> Option<&str> {
+        match self {
+            ContainerMessage::Envelope(envelope) => Some(&envelope.sender),
+            ContainerMessage::Reliable(reliable) => Some(&reliable.sender),
+            ContainerMessage::Ack(ack) => Some(&ack.sender),
+            ContainerMessage::Heartbeat(heartbeat) => Some(&heartbeat.sender),
+            ContainerMessage::ErrorMessage(error_message) => Some(&error_message.sender),
+            ContainerMessage::Subscribe(subscribe) => Some(&subscribe.container),
+            ContainerMessage::ProtocolHello(hello) => Some(&hello.container),
+            _ => None
+        }
+    }
+
+    /// The message this one wraps, if any
+    fn carried_message(&self) -> Option<&ContainerMessage> {
+        match self {
+            ContainerMessage::ForwardMessage(forward_message) => Some(&forward_message.message),
+            ContainerMessage::Envelope(envelope) => Some(&envelope.payload),
+            ContainerMessage::Reliable(reliable) => Some(&reliable.payload),
+            ContainerMessage::Traced(traced) => Some(&traced.payload),
+            _ => None
+        }
+    }
+
+    /// Checks the signature of a received message and decodes the message it wraps
+    fn open_signed(auth: &MessageAuth, message: ContainerMessage) -> ContainerMessage {
+        let signed = match message {
+            ContainerMessage::Signed(signed) => signed,
+            // A "Signed" message whose payload doesn't decode is already an UnknownMessage
+            ContainerMessage::UnknownMessage(unknown) if unknown.kind == SIGNED_KIND => {
+                return ContainerMessage::UnknownMessage(unknown);
+            }
+            unsigned => return ContainerMessage::UnknownMessage(UnknownMessage {
+                kind: unsigned.kind().to_string(),
+                protocol_version: PROTOCOL_VERSION,
+                payload: serde_json::to_value(&unsigned).unwrap_or(Value::Null),
+                reason: "unsigned messages are rejected".to_string()
+            })
+        };
+        let rejected = |kind: String, reason: String| ContainerMessage::UnknownMessage(UnknownMessage {
+            kind,
+            protocol_version: PROTOCOL_VERSION,
+            payload: serde_json::to_value(&signed).unwrap_or(Value::Null),
+            reason
+        });
+
+        if let Err(reason) = auth.verify(&signed) {
+            return rejected(SIGNED_KIND.to_string(), reason);
+        }
+        if !is_verified_peer(&signed.sender) {
+            return rejected(SIGNED_KIND.to_string(), format!("{} has no connection verified by its TLS certificate", signed.sender));
+        }
+        // Messages nested in the signed one are covered by its signature
+        let message = match serde_json::from_str(&signed.message) {
+            Ok(message) => ContainerMessage::decode_wire(message),
+            Err(error) => return rejected(SIGNED_KIND.to_string(), error.to_string())
+        };
+        if message.contains_signed() {
+            return rejected(SIGNED_KIND.to_string(), "a signed message can't carry another signed message".to_string());
+        }
+        // The signature only vouches for the signer, so every sender named inside must be the signer too
+        if let Some(claimed) = message.sender_other_than(&signed.sender) {
+            return rejected(message.kind().to_string(), format!("{} sent a message in the name of {}", signed.sender, claimed));
+        }
+        if message.carries_directive() && !auth.may_issue_directives(&signed.sender) {
+            return rejected(message.kind().to_string(), format!("{} may not issue directives", signed.sender));
+        }
+        message
+    }
+
+    /// Decodes the unsigned wire form of a message
+    fn decode_wire(value: Value) -> ContainerMessage {
+        let (kind, protocol_version, payload) = match value {
+            Value::Object(mut fields) if fields.contains_key("type") => {
+                let kind = fields.remove("type").and_then(|kind| kind.as_str().map(String::from)).unwrap_or_default();
+                let protocol_version = fields.get("protocol_version")
+                    .map(|version| version.as_u64().and_then(|version| u32::try_from(version).ok()).unwrap_or(0))
+                    .unwrap_or(PROTOCOL_VERSION);
+                (kind, protocol_version, fields.remove("payload").unwrap_or(Value::Null))
+            }
+            Value::Object(fields) if fields.len() == 1 => {
+                let (kind, payload) = fields.into_iter().next().expect("object has one field");
+                (kind, 0, payload)
+            }
+            other => return ContainerMessage::UnknownMessage(UnknownMessage {
+                kind: String::new(),
+                protocol_version: 0,
+                payload: other,
+                reason: "not a ContainerMessage".to_string()
+            })
+        };
+
+        let decoded = if is_supported_version(protocol_version) {
+            ContainerMessage::decode_payload(&kind, payload.clone())
         } else {
-            None
+            Err(format!("protocol version {} is older than the oldest supported version {}",
+                protocol_version, MIN_SUPPORTED_PROTOCOL_VERSION))
+        };
+        decoded.unwrap_or_else(|reason| ContainerMessage::UnknownMessage(UnknownMessage {
+            kind,
+            protocol_version,
+            payload,
+            reason
+        }))
+    }
+}
+
+impl Serialize for ContainerMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Unknown messages are passed on as they were received
+        let protocol_version = match self {
+            ContainerMessage::UnknownMessage(unknown) => unknown.protocol_version,
+            _ => PROTOCOL_VERSION
+        };
+        let mut wire = serializer.serialize_struct("ContainerMessage", 3)?;
+        wire.serialize_field("protocol_version", &protocol_version)?;
+        wire.serialize_field("type", self.kind())?;
+        self.serialize_payload::<S>(&mut wire)?;
+        wire.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for ContainerMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(ContainerMessage::from_wire)
+    }
+}
+
+/// Decoding of messages that didn't arrive from the network on their own, for `#[serde(deserialize_with = ..)]`
+///
+/// A message nested in another one (e.g. the payload of a Reliable) was checked along with the message it arrived
+/// in, and a message this container stored itself (e.g. in a snapshot) needs no checks, so both are decoded with
+/// [`ContainerMessage::from_local`].
+pub mod local {
+    // Package
+    use serde::{Deserialize, Deserializer};
+    use serde_json::Value;
+
+    // Local
+    use super::ContainerMessage;
+
+
+    /// Decodes a single message, e.g. into a `ContainerMessage` or `Box<ContainerMessage>` field
+    pub fn deserialize<'de, D, M>(deserializer: D) -> Result<M, D::Error>
+    where
+        D: Deserializer<'de>,
+        M: From<ContainerMessage>
+    {
+        Value::deserialize(deserializer).map(|value| M::from(ContainerMessage::from_local(value)))
+    }
+
+    /// Decodes a sequence of messages, e.g. into a `Vec<ContainerMessage>` or `VecDeque<ContainerMessage>` field
+    pub fn deserialize_all<'de, D, M>(deserializer: D) -> Result<M, D::Error>
+    where
+        D: Deserializer<'de>,
+        M: FromIterator<ContainerMessage>
+    {
+        Vec::<Value>::deserialize(deserializer).map(|values| values.into_iter().map(ContainerMessage::from_local).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use hyperion_framework::containerisation::traits::HyperionContainerDirectiveMessage;
+
+    use super::*;
+    use crate::messages::ack::Ack;
+    use crate::messages::config_reload_message::ConfigReloadMessage;
+    use crate::messages::example_message::ExampleMessage;
+    use crate::messages::message_variant::MessageVariant;
+    use crate::messages::reliable::Reliable;
+    use crate::messages::signed::{generate_key_pair, parse_public_key};
+
+    /// Authentication for `ComponentA` sending to `ComponentB`, which only accepts directives from `directive_sender`
+    fn sender_and_receiver(directive_sender: &str) -> (MessageAuth, MessageAuth) {
+        let (sender_key, sender_public_key) = generate_key_pair().unwrap();
+        let (receiver_key, _) = generate_key_pair().unwrap();
+        let peer_keys = HashMap::from([("ComponentA".to_string(), parse_public_key(&sender_public_key).unwrap())]);
+        let sender = MessageAuth::new("ComponentA".to_string(), &sender_key, HashMap::new(), Duration::from_secs(30),
+            Vec::new()).unwrap();
+        let receiver = MessageAuth::new("ComponentB".to_string(), &receiver_key, peer_keys, Duration::from_secs(30),
+            vec![directive_sender.to_string()]).unwrap();
+        (sender, receiver)
+    }
+
+    /// Sends a message from `sender` to `receiver`, through the wire format
+    fn transmit(sender: &MessageAuth, receiver: &MessageAuth, message: ContainerMessage) -> ContainerMessage {
+        let wire = serde_json::to_value(message.signed_by(sender)).unwrap();
+        assert_eq!(wire["type"], SIGNED_KIND);
+        ContainerMessage::open_signed(receiver, ContainerMessage::decode_wire(wire))
+    }
+
+    #[test]
+    fn local_only_messages_are_rejected_from_the_network() {
+        let reload = json!({"type": "ConfigReloadMessage", "payload": {"config_path": "/etc/passwd"}});
+        match ContainerMessage::from_wire(reload.clone()) {
+            ContainerMessage::UnknownMessage(unknown) => assert_eq!(unknown.kind, "ConfigReloadMessage"),
+            other => panic!("expected an UnknownMessage, got {:?}", other)
+        }
+        assert!(matches!(ContainerMessage::from_local(reload), ContainerMessage::ConfigReloadMessage(_)));
+    }
+
+    #[test]
+    fn containers_can_not_make_each_other_forward_messages() {
+        let forward = ForwardMessage::new(vec!["ComponentC".to_string()],
+            ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1)));
+        let wire = serde_json::to_value(ContainerMessage::ForwardMessage(forward)).unwrap();
+        assert!(matches!(ContainerMessage::from_wire(wire.clone()), ContainerMessage::UnknownMessage(_)));
+        // The console still forwards what it is given
+        assert!(matches!(ContainerMessage::from_local(wire), ContainerMessage::ForwardMessage(_)));
+    }
+
+    #[test]
+    fn nested_local_only_messages_are_rejected_from_the_network() {
+        let reliable = Reliable::new(1, "ComponentB".to_string(),
+            ContainerMessage::ConfigReloadMessage(ConfigReloadMessage::new("configuration.xml".to_string())));
+        let wire = serde_json::to_value(ContainerMessage::Reliable(reliable)).unwrap();
+        assert!(matches!(ContainerMessage::from_wire(wire.clone()), ContainerMessage::UnknownMessage(_)));
+        assert!(matches!(ContainerMessage::from_local(wire), ContainerMessage::Reliable(_)));
+    }
+
+    #[test]
+    fn serializing_a_message_does_not_sign_it() {
+        let wire = serde_json::to_value(ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1))).unwrap();
+        assert_eq!(wire["type"], "ExampleMessage");
+    }
+
+    #[test]
+    fn a_signed_message_is_opened_by_the_receiver() {
+        let (sender, receiver) = sender_and_receiver("ComponentA");
+        let message = ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1));
+        assert!(matches!(transmit(&sender, &receiver, message), ContainerMessage::ExampleMessage(_)));
+    }
+
+    #[test]
+    fn an_unsigned_message_is_rejected() {
+        let (_, receiver) = sender_and_receiver("ComponentA");
+        let message = ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1));
+        match ContainerMessage::open_signed(&receiver, message) {
+            ContainerMessage::UnknownMessage(unknown) => assert_eq!(unknown.reason, "unsigned messages are rejected"),
+            other => panic!("expected an UnknownMessage, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn directives_are_only_accepted_from_directive_senders() {
+        let shutdown = ContainerMessage::ContainerDirectiveMsg(ContainerDirective::SystemShutdown);
+
+        let (sender, receiver) = sender_and_receiver("ComponentA");
+        assert!(matches!(transmit(&sender, &receiver, shutdown.clone()), ContainerMessage::ContainerDirectiveMsg(_)));
+
+        let (sender, receiver) = sender_and_receiver("ComponentC");
+        match transmit(&sender, &receiver, shutdown) {
+            ContainerMessage::UnknownMessage(unknown) => assert_eq!(unknown.kind, "ContainerDirectiveMsg"),
+            other => panic!("expected an UnknownMessage, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn messages_in_another_containers_name_are_rejected() {
+        let (sender, receiver) = sender_and_receiver("ComponentA");
+        assert!(matches!(transmit(&sender, &receiver, ContainerMessage::Ack(Ack::new(1, "ComponentA".to_string()))),
+            ContainerMessage::Ack(_)));
+        match transmit(&sender, &receiver, ContainerMessage::Ack(Ack::new(1, "ComponentC".to_string()))) {
+            ContainerMessage::UnknownMessage(unknown) => assert_eq!(unknown.reason, "ComponentA sent a message in the name of ComponentC"),
+            other => panic!("expected an UnknownMessage, got {:?}", other)
+        }
+        // Senders named by nested messages are checked as well
+        let reply = Envelope::request(1, "ComponentC".to_string(), ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1)));
+        let reliable = Reliable::new(1, "ComponentA".to_string(), ContainerMessage::Envelope(reply));
+        assert!(matches!(transmit(&sender, &receiver, ContainerMessage::Reliable(reliable)), ContainerMessage::UnknownMessage(_)));
+    }
+
+    #[test]
+    fn nested_signed_messages_are_rejected() {
+        let (sender, receiver) = sender_and_receiver("ComponentA");
+        let shutdown = ContainerMessage::ContainerDirectiveMsg(ContainerDirective::SystemShutdown).signed_by(&sender);
+        let reliable = ContainerMessage::Reliable(Reliable::new(1, "ComponentA".to_string(), shutdown));
+        assert!(reliable.carries_directive());
+        match transmit(&sender, &receiver, reliable) {
+            ContainerMessage::UnknownMessage(unknown) => assert_eq!(unknown.reason, "a signed message can't carry another signed message"),
+            other => panic!("expected an UnknownMessage, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn a_signed_directive_is_still_carried_out_locally() {
+        let (sender, _) = sender_and_receiver("ComponentA");
+        let signed = ContainerMessage::ContainerDirectiveMsg(ContainerDirective::SystemShutdown).signed_by(&sender);
+        assert!(matches!(signed.get_container_directive_message(), Some(ContainerDirective::SystemShutdown)));
+        assert!(ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1)).signed_by(&sender)
+            .get_container_directive_message().is_none());
+    }
+
+    #[test]
+    fn versioned_messages_decode() {
+        let wire = json!({"protocol_version": PROTOCOL_VERSION, "type": "ExampleMessage", "payload": {"message": "x", "value": 41}});
+        assert!(matches!(ContainerMessage::decode_wire(wire), ContainerMessage::ExampleMessage(_)));
+        // Hand-written messages may leave the version out
+        let wire = json!({"type": "ExampleMessage", "payload": {"message": "x", "value": 41}});
+        assert!(matches!(ContainerMessage::decode_wire(wire), ContainerMessage::ExampleMessage(_)));
+    }
+
+    #[test]
+    fn unversioned_messages_decode_as_version_0() {
+        let wire = json!({"ExampleMessage": {"message": "x", "value": 41}});
+        assert!(matches!(ContainerMessage::decode_wire(wire), ContainerMessage::ExampleMessage(_)));
+    }
+
+    #[test]
+    fn messages_this_build_does_not_understand_become_unknown() {
+        let wire = json!({"protocol_version": PROTOCOL_VERSION + 1, "type": "NewerMessage", "payload": {"a": 1}});
+        match ContainerMessage::decode_wire(wire) {
+            ContainerMessage::UnknownMessage(unknown) => {
+                assert_eq!(unknown.kind, "NewerMessage");
+                assert_eq!(unknown.protocol_version, PROTOCOL_VERSION + 1);
+                assert_eq!(unknown.payload, json!({"a": 1}));
+            }
+            other => panic!("expected an UnknownMessage, got {:?}", other)
+        }
+        assert!(matches!(ContainerMessage::decode_wire(json!([1, 2])), ContainerMessage::UnknownMessage(_)));
+        let wire = json!({"type": "ExampleMessage", "payload": {"message": 7}});
+        assert!(matches!(ContainerMessage::decode_wire(wire), ContainerMessage::UnknownMessage(_)));
+    }
+
+    #[test]
+    fn unknown_messages_are_passed_on_as_they_were_received() {
+        let wire = json!({"protocol_version": PROTOCOL_VERSION + 1, "type": "NewerMessage", "payload": {"a": 1}});
+        let unknown = ContainerMessage::decode_wire(wire.clone());
+        assert_eq!(serde_json::to_value(&unknown).unwrap(), wire);
+    }
+
+    #[test]
+    fn kinds_name_every_known_message() {
+        assert_eq!(ContainerMessage::KINDS.len(), 16);
+        assert!(ContainerMessage::KINDS.contains(&"ContainerDirectiveMsg"));
+        assert!(ContainerMessage::KINDS.contains(&"ExampleMessage"));
+        assert!(ContainerMessage::KINDS.contains(&"Signed"));
+        assert!(!ContainerMessage::KINDS.contains(&"UnknownMessage"));
+        assert_eq!(ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1)).kind(), "ExampleMessage");
+        assert_eq!(<ExampleMessage as MessageVariant<ContainerMessage>>::KIND, "ExampleMessage");
+    }
+
+    #[test]
+    fn unknown_messages_keep_the_kind_they_were_sent_as() {
+        let unknown = ContainerMessage::UnknownMessage(UnknownMessage {
+            kind: "NewerMessage".to_string(),
+            protocol_version: PROTOCOL_VERSION + 1,
+            payload: json!({}),
+            reason: "unknown message type `NewerMessage`".to_string()
+        });
+        assert_eq!(unknown.kind(), "NewerMessage");
+    }
+
+    #[test]
+    fn only_messages_marked_local_are_local_only() {
+        let reload = ContainerMessage::ConfigReloadMessage(ConfigReloadMessage::new("configuration.xml".to_string()));
+        assert!(reload.is_local_only());
+        assert!(!ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1)).is_local_only());
+        assert!(!ContainerMessage::ContainerDirectiveMsg(ContainerDirective::SystemShutdown).is_local_only());
+    }
+
+    #[test]
+    fn only_the_directive_variant_is_a_container_directive() {
+        let shutdown = ContainerMessage::ContainerDirectiveMsg(ContainerDirective::SystemShutdown);
+        assert!(matches!(shutdown.get_container_directive_message(), Some(ContainerDirective::SystemShutdown)));
+        assert!(ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1))
+            .get_container_directive_message().is_none());
+    }
+
+    #[test]
+    fn messages_convert_to_and_from_their_variant() {
+        let message: ContainerMessage = ExampleMessage::new("x".to_string(), 41).into();
+        assert!(matches!(message, ContainerMessage::ExampleMessage(_)));
+        let example = ExampleMessage::try_from(message).unwrap();
+        assert_eq!(example.value, 41);
+
+        let shutdown = ContainerMessage::ContainerDirectiveMsg(ContainerDirective::SystemShutdown);
+        match ExampleMessage::try_from(shutdown) {
+            Err(ContainerMessage::ContainerDirectiveMsg(ContainerDirective::SystemShutdown)) => {}
+            other => panic!("expected the directive back, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn known_messages_survive_the_wire() {
+        let message = ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 41));
+        let wire = serde_json::to_value(&message).unwrap();
+        assert_eq!(wire, json!({"protocol_version": PROTOCOL_VERSION, "type": "ExampleMessage",
+            "payload": {"message": "x", "value": 41}}));
+        match ContainerMessage::decode_wire(wire) {
+            ContainerMessage::ExampleMessage(example) => {
+                assert_eq!(example.message, "x");
+                assert_eq!(example.value, 41);
+            }
+            other => panic!("expected an ExampleMessage, got {:?}", other)
         }
     }
 }
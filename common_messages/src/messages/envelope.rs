@@ -0,0 +1,60 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Package
+use serde::{Serialize, Deserialize};
+
+// Local
+use crate::messages::container_message::{local, ContainerMessage};
+
+
+/// Wraps a message sent as part of a request/response interaction between containers.
+///
+/// A request names the container its reply should go to. The reply carries the request's correlation id
+/// so the requester can match it to the request awaiting it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Envelope {
+    /// Shared by a request and its reply
+    pub correlation_id: u64,
+    /// Container name of the sender
+    pub sender: String,
+    /// Container the reply should be sent to, or `None` if this envelope is itself a reply
+    pub reply_to: Option<String>,
+    #[serde(deserialize_with = "local::deserialize")]
+    pub payload: Box<ContainerMessage>
+}
+
+impl Envelope {
+    /// Creates a request whose reply is sent back to the sender
+    pub fn request(correlation_id: u64, sender: String, payload: ContainerMessage) -> Envelope {
+        Envelope {
+            correlation_id,
+            reply_to: Some(sender.clone()),
+            sender,
+            payload: Box::new(payload)
+        }
+    }
+
+    /// Creates the reply to this request
+    pub fn reply(&self, sender: String, payload: ContainerMessage) -> Envelope {
+        Envelope {
+            correlation_id: self.correlation_id,
+            sender,
+            reply_to: None,
+            payload: Box::new(payload)
+        }
+    }
+
+    /// Whether this envelope answers a request
+    pub fn is_reply(&self) -> bool {
+        self.reply_to.is_none()
+    }
+}
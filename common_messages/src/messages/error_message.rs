@@ -0,0 +1,35 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Package
+use serde::{Serialize, Deserialize};
+
+
+/// Tells the sender of a message that it was not processed, e.g. because the component was Dormant
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ErrorMessage {
+    /// Name of the container that refused the message
+    pub sender: String,
+    /// Message type that was refused
+    pub kind: String,
+    /// Why the message was refused
+    pub reason: String
+}
+
+impl ErrorMessage {
+    pub fn new(sender: String, kind: String, reason: String) -> ErrorMessage {
+        ErrorMessage {
+            sender,
+            kind,
+            reason
+        }
+    }
+}
@@ -0,0 +1,39 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Package
+use serde::{Serialize, Deserialize};
+
+// Local
+use crate::messages::container_message::{local, ContainerMessage};
+
+
+/// Asks the receiving component to send the wrapped message on to other containers.
+///
+/// This lets a message injected locally (e.g. from the console) reach remote containers, as only the
+/// component can hand messages to Hyperion's client broker. It is only accepted from within the container,
+/// so other containers can't use the component as a relay.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ForwardMessage {
+    /// Container names as given in network_topology.xml
+    pub targets: Vec<String>,
+    #[serde(deserialize_with = "local::deserialize")]
+    pub message: Box<ContainerMessage>
+}
+
+impl ForwardMessage {
+    pub fn new(targets: Vec<String>, message: ContainerMessage) -> ForwardMessage {
+        ForwardMessage {
+            targets,
+            message: Box::new(message)
+        }
+    }
+}
@@ -0,0 +1,46 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+// Package
+use serde::{Serialize, Deserialize};
+
+
+/// Sent periodically over every client connection so containers know which of their peers are up.
+///
+/// A container receiving a heartbeat that isn't itself a reply answers it, echoing the send time so the
+/// sender can measure the round trip.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Heartbeat {
+    /// Container name of the sender
+    pub sender: String,
+    /// When the heartbeat being answered was sent, in milliseconds since the Unix epoch of its sender's clock
+    pub sent_at_ms: u64,
+    /// Whether this heartbeat answers one from the receiver, which must not be answered again
+    pub is_reply: bool
+}
+
+impl Heartbeat {
+    pub fn new(sender: String, sent_at_ms: u64) -> Heartbeat {
+        Heartbeat {
+            sender,
+            sent_at_ms,
+            is_reply: false
+        }
+    }
+
+    /// Creates the answer to this heartbeat
+    pub fn reply(&self, sender: String) -> Heartbeat {
+        Heartbeat {
+            sender,
+            sent_at_ms: self.sent_at_ms,
+            is_reply: true
+        }
+    }
+}
@@ -0,0 +1,17 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+/// Implemented by `#[derive(HyperionMessage)]` for every message type carried in a variant of the message
+/// enum `E`, tying the type to the variant's wire name.
+pub trait MessageVariant<E>: TryFrom<E, Error = E> + Into<E> {
+    /// Name of the variant carrying this type, as returned by the enum's `kind()`
+    const KIND: &'static str;
+}
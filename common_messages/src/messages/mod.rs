@@ -9,6 +9,20 @@
 // Example written by Robert Hannah 2025
 // -------------------------------------------------------------------------------------------------
 
+pub mod ack;
 pub mod another_example_message;
+pub mod config_reload_message;
 pub mod container_message;
+pub mod envelope;
+pub mod error_message;
 pub mod example_message;
+pub mod forward_message;
+pub mod heartbeat;
+pub mod message_variant;
+pub mod peer_status;
+pub mod protocol_hello;
+pub mod reliable;
+pub mod signed;
+pub mod subscribe;
+pub mod traced;
+pub mod unknown_message;
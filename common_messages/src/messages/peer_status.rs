@@ -0,0 +1,60 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+// Standard
+use std::fmt;
+
+// Package
+use serde::{Serialize, Deserialize};
+
+
+/// Liveness of a peer container, judged by its heartbeats
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerState {
+    /// Not heard from since this container started
+    Unknown,
+    /// Heartbeats arrive in time
+    Connected,
+    /// Heartbeats have been missed, the peer may be down or slow
+    Suspect,
+    /// No heartbeat for long enough to consider the peer down
+    Down
+}
+
+impl fmt::Display for PeerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PeerState::Unknown => "Unknown",
+            PeerState::Connected => "Connected",
+            PeerState::Suspect => "Suspect",
+            PeerState::Down => "Down"
+        };
+        f.write_str(name)
+    }
+}
+
+/// Tells the component that a peer's state has changed. Raised locally, never sent between containers.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerStatus {
+    /// Container name of the peer
+    pub container: String,
+    pub previous_state: PeerState,
+    pub state: PeerState
+}
+
+impl PeerStatus {
+    pub fn new(container: String, previous_state: PeerState, state: PeerState) -> PeerStatus {
+        PeerStatus {
+            container,
+            previous_state,
+            state
+        }
+    }
+}
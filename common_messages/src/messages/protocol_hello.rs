@@ -0,0 +1,62 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Package
+use serde::{Serialize, Deserialize};
+
+// Version of the message protocol spoken by this build. Bump it whenever ContainerMessage or one of the
+// messages it carries changes in a way older containers can't decode
+pub const PROTOCOL_VERSION: u32 = 1;
+// Oldest protocol version this build still decodes. Version 0 is the unversioned format used before
+// messages carried a protocol version
+pub const MIN_SUPPORTED_PROTOCOL_VERSION: u32 = 0;
+
+
+/// Exchanged when containers connect so incompatible builds are reported instead of silently failing
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProtocolHello {
+    /// Container name of the sender
+    pub container: String,
+    pub protocol_version: u32,
+    pub min_supported_version: u32,
+    /// Whether this hello answers one from the receiver, which must not be answered again
+    pub is_reply: bool
+}
+
+impl ProtocolHello {
+    pub fn new(container: String) -> ProtocolHello {
+        ProtocolHello {
+            container,
+            protocol_version: PROTOCOL_VERSION,
+            min_supported_version: MIN_SUPPORTED_PROTOCOL_VERSION,
+            is_reply: false
+        }
+    }
+
+    pub fn reply(container: String) -> ProtocolHello {
+        ProtocolHello {
+            is_reply: true,
+            ..ProtocolHello::new(container)
+        }
+    }
+
+    /// Whether this build and the sender of this hello can decode each other's messages
+    pub fn is_compatible(&self) -> bool {
+        is_supported_version(self.protocol_version) && PROTOCOL_VERSION >= self.min_supported_version
+    }
+}
+
+/// Whether this build still decodes messages of the given protocol version
+// The minimum is 0 until the first incompatible protocol change, which makes the comparison always true for now
+#[allow(clippy::absurd_extreme_comparisons)]
+pub fn is_supported_version(protocol_version: u32) -> bool {
+    protocol_version >= MIN_SUPPORTED_PROTOCOL_VERSION
+}
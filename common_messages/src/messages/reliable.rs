@@ -0,0 +1,41 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Package
+use serde::{Serialize, Deserialize};
+
+// Local
+use crate::messages::container_message::{local, ContainerMessage};
+
+
+/// Wraps a message sent with at-least-once delivery.
+///
+/// The receiving container answers with an [`Ack`](crate::messages::ack::Ack) naming the message id and
+/// ignores ids it has already seen, so the sender can retransmit until the message is acknowledged.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Reliable {
+    /// Unique among the messages of the sender
+    pub message_id: u64,
+    /// Container name of the sender, which the acknowledgement is sent to
+    pub sender: String,
+    #[serde(deserialize_with = "local::deserialize")]
+    pub payload: Box<ContainerMessage>
+}
+
+impl Reliable {
+    pub fn new(message_id: u64, sender: String, payload: ContainerMessage) -> Reliable {
+        Reliable {
+            message_id,
+            sender,
+            payload: Box::new(payload)
+        }
+    }
+}
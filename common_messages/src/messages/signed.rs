@@ -0,0 +1,319 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Authentication of the messages exchanged between containers.
+//!
+//! Once a [`MessageAuth`] is installed, every `ContainerMessage` sent to another container is wrapped in a
+//! [`Signed`] message carrying the sender's name, a timestamp, a random nonce and an Ed25519 signature made with
+//! the sender's own private key. Receivers check the signature against the public key they hold for the named
+//! sender, so a container can't sign as another one, nor name another one as the sender inside the message.
+//! Received messages that are unsigned, carry an invalid signature, come from a container whose key isn't known,
+//! were signed longer ago than the allowed age or repeat the nonce of a message already received decode as an
+//! `UnknownMessage`, so neither Hyperion nor the component acts on them. Directives are additionally only accepted
+//! from the containers allowed to issue them.
+//!
+//! Serializing a message never signs it, so messages written to disk, e.g. queued or snapshotted ones, don't go
+//! stale while stored. Messages are signed where they leave the container, with
+//! [`ContainerMessage::signed_by`](crate::messages::container_message::ContainerMessage::signed_by).
+
+// Standard
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Package
+use ring::rand::{SecureRandom, SystemRandom};
+use hyperion_framework::messages::container_directive::ContainerDirective;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Serialize, Deserialize};
+
+
+/// Message type of a signed message on the wire
+pub const SIGNED_KIND: &str = "Signed";
+
+/// Authentication used by every message sent and received by this process, once installed
+static MESSAGE_AUTH: OnceLock<MessageAuth> = OnceLock::new();
+
+
+/// The wire form of a message signed by its sender
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Signed {
+    /// Container name of the sender
+    pub sender: String,
+    /// When the message was signed, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    /// Random number used once, so a captured message can't be replayed while its timestamp is still valid
+    pub nonce: u64,
+    /// The message exactly as the sender serialized it, so the signature can be checked on the same bytes
+    pub message: String,
+    /// Hex encoded Ed25519 signature of the sender, timestamp, nonce and message
+    pub signature: String,
+    /// The container directive that was signed, so Hyperion carries out a signed directive handed to it from
+    /// within the container (e.g. `SystemShutdown` from the console) before passing it on. Never sent.
+    #[serde(skip)]
+    pub directive: Option<ContainerDirective>
+}
+
+impl Signed {
+    /// The container directive this message was signed from within the container
+    pub fn container_directive(&self) -> Option<&ContainerDirective> {
+        self.directive.as_ref()
+    }
+}
+
+/// This container's signing key and the rules received messages are checked against
+pub struct MessageAuth {
+    container_name: String,
+    key_pair: Ed25519KeyPair,
+    /// Public keys of the containers whose messages are accepted, by container name
+    peer_keys: HashMap<String, Vec<u8>>,
+    max_age: Duration,
+    directive_senders: Vec<String>,
+    /// Nonces of the messages received within the allowed age, with their timestamps, by sender
+    seen_nonces: Mutex<HashMap<String, HashMap<u64, u64>>>,
+    random: SystemRandom
+}
+
+impl MessageAuth {
+    /// # Arguments
+    ///
+    /// * `container_name` - Name of this container, which its messages are signed as
+    /// * `private_key` - This container's Ed25519 key pair as a PKCS#8 document
+    /// * `peer_keys` - Ed25519 public keys of the containers whose messages are accepted, by container name
+    /// * `max_age` - How far a received message's timestamp may be from the local clock
+    /// * `directive_senders` - Containers allowed to send container and component directives
+    ///
+    /// # Returns
+    ///
+    /// * `Result<MessageAuth, String>` - Why the private key can't be used
+    pub fn new(container_name: String, private_key: &[u8], mut peer_keys: HashMap<String, Vec<u8>>, max_age: Duration,
+        directive_senders: Vec<String>) -> Result<MessageAuth, String> {
+        let key_pair = Ed25519KeyPair::from_pkcs8(private_key)
+            .map_err(|error| format!("the private key of {} is not an Ed25519 PKCS#8 key: {}", container_name, error))?;
+        // Messages a container sends to itself are checked like any other
+        peer_keys.insert(container_name.clone(), key_pair.public_key().as_ref().to_vec());
+        Ok(MessageAuth {
+            container_name,
+            key_pair,
+            peer_keys,
+            max_age,
+            directive_senders,
+            seen_nonces: Mutex::new(HashMap::new()),
+            random: SystemRandom::new()
+        })
+    }
+
+    /// Signs every message this process sends and checks every message it receives from now on
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - False if authentication had already been installed, which is then left unchanged
+    pub fn install(self) -> bool {
+        MESSAGE_AUTH.set(self).is_ok()
+    }
+
+    /// The installed authentication, if any
+    pub fn installed() -> Option<&'static MessageAuth> {
+        MESSAGE_AUTH.get()
+    }
+
+    /// Signs the serialized form of a message as this container
+    pub fn sign(&self, message: String) -> Signed {
+        let timestamp_ms = now_ms();
+        let mut nonce = [0u8; 8];
+        // The system's random number generator only fails if the operating system can't provide randomness at all
+        self.random.fill(&mut nonce).expect("the system random number generator failed");
+        let nonce = u64::from_be_bytes(nonce);
+        let signature = self.key_pair.sign(&signing_input(&self.container_name, timestamp_ms, nonce, &message));
+        Signed {
+            sender: self.container_name.clone(),
+            timestamp_ms,
+            nonce,
+            message,
+            signature: to_hex(signature.as_ref()),
+            directive: None
+        }
+    }
+
+    /// Checks the signature, age and nonce of a received message
+    ///
+    /// A message that passes is remembered until it is too old to be accepted anyway, so the same message is only
+    /// accepted once.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), String>` - Why the message must be rejected
+    pub fn verify(&self, signed: &Signed) -> Result<(), String> {
+        let public_key = self.peer_keys.get(&signed.sender)
+            .ok_or_else(|| format!("there is no public key for {}", signed.sender))?;
+        let signature = from_hex(&signed.signature).ok_or_else(|| "the signature is not hex encoded".to_string())?;
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(&signing_input(&signed.sender, signed.timestamp_ms, signed.nonce, &signed.message), &signature)
+            .map_err(|_| format!("the signature of {}'s message is invalid", signed.sender))?;
+
+        let now_ms = now_ms();
+        let max_age_ms = self.max_age.as_millis() as u64;
+        let age_ms = now_ms.abs_diff(signed.timestamp_ms);
+        if age_ms > max_age_ms {
+            return Err(format!("{}'s message is timestamped {} ms off the local clock, more than the allowed {} ms",
+                signed.sender, age_ms, max_age_ms));
+        }
+
+        let mut seen_nonces = self.seen_nonces.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let seen = seen_nonces.entry(signed.sender.clone()).or_default();
+        seen.retain(|_, timestamp_ms| now_ms.abs_diff(*timestamp_ms) <= max_age_ms);
+        if seen.insert(signed.nonce, signed.timestamp_ms).is_some() {
+            return Err(format!("{}'s message with nonce {} was already received", signed.sender, signed.nonce));
+        }
+        Ok(())
+    }
+
+    /// Whether the container may send container and component directives
+    pub fn may_issue_directives(&self, sender: &str) -> bool {
+        self.directive_senders.iter().any(|directive_sender| directive_sender == sender)
+    }
+}
+
+/// Generates an Ed25519 key pair for a container
+///
+/// # Returns
+///
+/// * `Result<(Vec<u8>, String), String>` - The key pair as a PKCS#8 document and the hex encoded public key
+pub fn generate_key_pair() -> Result<(Vec<u8>, String), String> {
+    let document = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+        .map_err(|_| "the system random number generator failed".to_string())?;
+    let key_pair = Ed25519KeyPair::from_pkcs8(document.as_ref()).map_err(|error| error.to_string())?;
+    Ok((document.as_ref().to_vec(), to_hex(key_pair.public_key().as_ref())))
+}
+
+/// Decodes a hex encoded public key, e.g. as written by [`generate_key_pair`]
+pub fn parse_public_key(hex: &str) -> Option<Vec<u8>> {
+    from_hex(hex.trim()).filter(|key| key.len() == ED25519_PUBLIC_KEY_LEN)
+}
+
+/// Length of an Ed25519 public key in bytes
+const ED25519_PUBLIC_KEY_LEN: usize = 32;
+
+/// The bytes covered by the signature, with the sender's length first so no two messages share them
+fn signing_input(sender: &str, timestamp_ms: u64, nonce: u64, message: &str) -> Vec<u8> {
+    format!("{}:{}\n{}\n{}\n{}", sender.len(), sender, timestamp_ms, nonce, message).into_bytes()
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_millis() as u64).unwrap_or_default()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        // Writing to a String can't fail
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    hex
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len()).step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok())
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth(name: &str, peers: &[(&str, &MessageAuth)], directive_senders: &[&str]) -> (MessageAuth, Vec<u8>) {
+        let (private_key, _) = generate_key_pair().unwrap();
+        let peer_keys = peers.iter()
+            .map(|(peer, auth)| (peer.to_string(), auth.key_pair.public_key().as_ref().to_vec()))
+            .collect();
+        let auth = MessageAuth::new(name.to_string(), &private_key, peer_keys, Duration::from_secs(30),
+            directive_senders.iter().map(|sender| sender.to_string()).collect()).unwrap();
+        (auth, private_key)
+    }
+
+    #[test]
+    fn a_signed_message_is_accepted_once() {
+        let (sender, _) = auth("ComponentA", &[], &[]);
+        let (receiver, _) = auth("ComponentB", &[("ComponentA", &sender)], &[]);
+        let signed = sender.sign("{}".to_string());
+
+        assert!(receiver.verify(&signed).is_ok());
+        assert!(receiver.verify(&signed).unwrap_err().contains("already received"));
+        assert!(receiver.verify(&sender.sign("{}".to_string())).is_ok());
+    }
+
+    #[test]
+    fn a_tampered_message_is_rejected() {
+        let (sender, _) = auth("ComponentA", &[], &[]);
+        let (receiver, _) = auth("ComponentB", &[("ComponentA", &sender)], &[]);
+
+        let mut signed = sender.sign("{\"a\":1}".to_string());
+        signed.message = "{\"a\":2}".to_string();
+        assert!(receiver.verify(&signed).unwrap_err().contains("invalid"));
+
+        let mut signed = sender.sign("{}".to_string());
+        signed.nonce = signed.nonce.wrapping_add(1);
+        assert!(receiver.verify(&signed).unwrap_err().contains("invalid"));
+    }
+
+    #[test]
+    fn a_container_can_not_sign_as_another() {
+        let (component_a, _) = auth("ComponentA", &[], &[]);
+        let (component_c, _) = auth("ComponentC", &[], &[]);
+        let (receiver, _) = auth("ComponentB", &[("ComponentA", &component_a), ("ComponentC", &component_c)], &[]);
+
+        let mut signed = component_c.sign("{}".to_string());
+        signed.sender = "ComponentA".to_string();
+        assert!(receiver.verify(&signed).is_err());
+
+        let unknown = auth("ComponentD", &[], &[]).0.sign("{}".to_string());
+        assert!(receiver.verify(&unknown).unwrap_err().contains("no public key"));
+    }
+
+    #[test]
+    fn a_stale_message_is_rejected() {
+        let (sender, private_key) = auth("ComponentA", &[], &[]);
+        let (receiver, _) = auth("ComponentB", &[("ComponentA", &sender)], &[]);
+
+        let timestamp_ms = now_ms() - 60_000;
+        let key_pair = Ed25519KeyPair::from_pkcs8(&private_key).unwrap();
+        let signature = key_pair.sign(&signing_input("ComponentA", timestamp_ms, 7, "{}"));
+        let signed = Signed {
+            sender: "ComponentA".to_string(),
+            timestamp_ms,
+            nonce: 7,
+            message: "{}".to_string(),
+            signature: to_hex(signature.as_ref()),
+            directive: None
+        };
+        assert!(receiver.verify(&signed).unwrap_err().contains("off the local clock"));
+    }
+
+    #[test]
+    fn only_listed_containers_may_issue_directives() {
+        let (receiver, _) = auth("ComponentB", &[], &["ComponentA"]);
+        assert!(receiver.may_issue_directives("ComponentA"));
+        assert!(!receiver.may_issue_directives("ComponentC"));
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        let bytes = vec![0, 1, 0xab, 0xff];
+        assert_eq!(from_hex(&to_hex(&bytes)), Some(bytes));
+        assert_eq!(from_hex("abc"), None);
+        assert_eq!(parse_public_key("00ff"), None);
+    }
+}
@@ -0,0 +1,46 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Package
+use serde::{Serialize, Deserialize};
+
+
+/// Announces the topics a container subscribes to.
+///
+/// Every container announces its subscriptions to the containers it connects to when it starts. A container
+/// receiving an announcement that isn't itself a reply answers with its own, so both sides know each
+/// other's subscriptions whichever started first.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Subscribe {
+    /// Container name of the subscriber
+    pub container: String,
+    /// Every topic the container subscribes to, replacing any it announced before
+    pub topics: Vec<String>,
+    /// Whether this announcement answers one from the receiver, which must not be answered again
+    pub is_reply: bool
+}
+
+impl Subscribe {
+    pub fn new(container: String, topics: Vec<String>) -> Subscribe {
+        Subscribe {
+            container,
+            topics,
+            is_reply: false
+        }
+    }
+
+    pub fn reply(container: String, topics: Vec<String>) -> Subscribe {
+        Subscribe {
+            is_reply: true,
+            ..Subscribe::new(container, topics)
+        }
+    }
+}
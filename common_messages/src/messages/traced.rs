@@ -0,0 +1,35 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+// Package
+use serde::{Serialize, Deserialize};
+
+// Local
+use crate::messages::container_message::{local, ContainerMessage};
+
+
+/// Carries the trace context of the work that produced a message, so the receiving container's spans
+/// join the same trace.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Traced {
+    /// W3C trace context of the sending span, e.g. `00-<trace id>-<span id>-01`
+    pub traceparent: String,
+    #[serde(deserialize_with = "local::deserialize")]
+    pub payload: Box<ContainerMessage>
+}
+
+impl Traced {
+    pub fn new(traceparent: String, payload: ContainerMessage) -> Traced {
+        Traced {
+            traceparent,
+            payload: Box::new(payload)
+        }
+    }
+}
@@ -0,0 +1,28 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Package
+use serde::{Serialize, Deserialize};
+
+
+/// A message this build could not decode, e.g. a variant or field added by a newer container.
+///
+/// The raw payload is kept so the message can still be forwarded unchanged.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UnknownMessage {
+    /// Message type named by the sender
+    pub kind: String,
+    /// Protocol version of the sender, 0 for the unversioned format
+    pub protocol_version: u32,
+    pub payload: serde_json::Value,
+    /// Why the message could not be decoded
+    pub reason: String
+}
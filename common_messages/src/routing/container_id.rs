@@ -0,0 +1,110 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Standard
+use std::fmt;
+use std::str::FromStr;
+
+// Package
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+use serde::{Serialize, Deserialize};
+
+// Local
+use crate::messages::container_message::ContainerMessage;
+
+// Every container that can be the target of a message. Routing through these rather than bare strings
+// means a typo is a compile error, and the runtime checks at startup that each target a component
+// declares is one of its client_connections in network_topology.xml
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ContainerId {
+    ComponentA,
+    ComponentB,
+    // Add more containers as needed
+}
+
+impl ContainerId {
+    /// Every known container
+    pub const ALL: &'static [ContainerId] = &[ContainerId::ComponentA, ContainerId::ComponentB];
+
+    /// Container name as given in network_topology.xml
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContainerId::ComponentA => "ComponentA",
+            ContainerId::ComponentB => "ComponentB"
+        }
+    }
+
+    /// The container a container name belongs to, which may name one of its instances, e.g. `ComponentB-1`
+    pub fn of_container(name: &str) -> Result<ContainerId, String> {
+        let base = match name.split_once('-') {
+            Some((base, instance)) if !instance.is_empty() => base,
+            _ => name
+        };
+        base.parse().map_err(|_| format!("unknown container `{}`", name))
+    }
+
+    /// Wraps a message for Hyperion's client broker to send to the given containers
+    pub fn broker_message(targets: &[ContainerId], message: ContainerMessage) -> ClientBrokerMessage<ContainerMessage> {
+        ClientBrokerMessage::new(targets.iter().map(ContainerId::as_str).collect(), message)
+    }
+}
+
+impl fmt::Display for ContainerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ContainerId {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        ContainerId::ALL.iter()
+            .find(|id| id.as_str() == name)
+            .copied()
+            .ok_or_else(|| format!("unknown container `{}`", name))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn container_ids_parse_from_their_names() {
+        for id in ContainerId::ALL {
+            assert_eq!(id.as_str().parse::<ContainerId>(), Ok(*id));
+            assert_eq!(id.to_string(), id.as_str());
+        }
+        assert_eq!("ComponentC".parse::<ContainerId>(), Err("unknown container `ComponentC`".to_string()));
+        assert!("componentb".parse::<ContainerId>().is_err());
+    }
+
+    #[test]
+    fn broker_messages_name_their_targets() {
+        let message = ContainerMessage::ContainerDirectiveMsg(
+            hyperion_framework::messages::container_directive::ContainerDirective::Shutdown);
+        let broker_message = ContainerId::broker_message(&[ContainerId::ComponentB, ContainerId::ComponentA], message);
+        assert_eq!(broker_message.client_name_list, vec!["ComponentB".to_string(), "ComponentA".to_string()]);
+        assert!(matches!(broker_message.message, ContainerMessage::ContainerDirectiveMsg(_)));
+    }
+
+    #[test]
+    fn instances_belong_to_their_container() {
+        assert_eq!(ContainerId::of_container("ComponentB"), Ok(ContainerId::ComponentB));
+        assert_eq!(ContainerId::of_container("ComponentB-1"), Ok(ContainerId::ComponentB));
+        assert_eq!(ContainerId::of_container("ComponentB-"), Err("unknown container `ComponentB-`".to_string()));
+        assert_eq!(ContainerId::of_container("ComponentC-1"), Err("unknown container `ComponentC-1`".to_string()));
+    }
+}
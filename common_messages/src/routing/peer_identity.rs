@@ -0,0 +1,113 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Containers whose identity this container has verified on the transport, i.e. by their TLS certificate.
+//!
+//! The TLS proxy records every container with an open, mutually authenticated connection to this one in the
+//! installed [`VerifiedPeers`]. From then on, signed messages are only accepted from containers with such a
+//! connection, so a leaked key can't be used from a connection authenticated as another container. A connection
+//! that just closed still counts for a moment, as its last messages may still be decoding.
+
+// Standard
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+
+/// How long a container still counts as connected after its last connection closed
+pub const CLOSED_CONNECTION_GRACE: Duration = Duration::from_secs(5);
+
+/// Verified connections of this process, once the transport checks identities at all
+static VERIFIED_PEERS: OnceLock<VerifiedPeers> = OnceLock::new();
+
+
+#[derive(Default)]
+struct PeerConnections {
+    open: usize,
+    last_closed: Option<Instant>
+}
+
+/// The containers with a verified connection to this one
+#[derive(Default)]
+pub struct VerifiedPeers {
+    peers: Mutex<HashMap<String, PeerConnections>>
+}
+
+/// Registers an open connection of a verified container for as long as it lives
+pub struct VerifiedConnection<'a> {
+    peers: &'a VerifiedPeers,
+    container: String
+}
+
+impl VerifiedPeers {
+    pub fn new() -> VerifiedPeers {
+        VerifiedPeers::default()
+    }
+
+    /// The verified peers of this process, which every received signed message is checked against from now on
+    pub fn install() -> &'static VerifiedPeers {
+        VERIFIED_PEERS.get_or_init(VerifiedPeers::new)
+    }
+
+    /// The installed verified peers, if the transport checks identities
+    pub fn installed() -> Option<&'static VerifiedPeers> {
+        VERIFIED_PEERS.get()
+    }
+
+    /// Records a new connection whose certificate was verified for the given container
+    pub fn open(&self, container: &str) -> VerifiedConnection<'_> {
+        self.lock().entry(container.to_string()).or_default().open += 1;
+        VerifiedConnection { peers: self, container: container.to_string() }
+    }
+
+    /// Whether the container has an open verified connection, or had one a moment ago
+    pub fn is_connected(&self, container: &str) -> bool {
+        self.lock().get(container).is_some_and(|connections| connections.open > 0
+            || connections.last_closed.is_some_and(|last_closed| last_closed.elapsed() <= CLOSED_CONNECTION_GRACE))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, PeerConnections>> {
+        self.peers.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Drop for VerifiedConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(connections) = self.peers.lock().get_mut(&self.container) {
+            connections.open = connections.open.saturating_sub(1);
+            connections.last_closed = Some(Instant::now());
+        }
+    }
+}
+
+/// Whether messages signed by the container are acceptable on the transport
+///
+/// Always true unless verified peers have been installed.
+pub fn is_verified_peer(container: &str) -> bool {
+    VerifiedPeers::installed().is_none_or(|peers| peers.is_connected(container))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_connected_containers_are_verified() {
+        let peers = VerifiedPeers::new();
+        assert!(!peers.is_connected("ComponentA"));
+        let connection = peers.open("ComponentA");
+        assert!(peers.is_connected("ComponentA"));
+        drop(connection);
+        // Still accepted within the grace period after closing
+        assert!(peers.is_connected("ComponentA"));
+        assert!(!peers.is_connected("ComponentB"));
+    }
+}
@@ -0,0 +1,17 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Topics components publish messages to. Subscribers declare the topics they want in the <subscriptions>
+// section of their configuration.xml, so a new consumer doesn't need any change to the producer
+
+
+/// The example's ExampleMessage exchange
+pub const EXAMPLE_TOPIC: &str = "examples";
@@ -0,0 +1,96 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Package
+use common_messages::messages::another_example_message::AnotherExampleMessage;
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::messages::envelope::Envelope;
+use common_messages::messages::example_message::ExampleMessage;
+use common_messages::routing::topic::EXAMPLE_TOPIC;
+use hyperion_examples_runtime::component::HandlerContext;
+use hyperion_examples_runtime::request::Requester;
+use hyperion_examples_runtime::router::{HandlerResult, Handles, MessageRouter};
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+
+use async_trait::async_trait;
+
+// Local
+use crate::component_logic::internal_logic::process_message;
+
+
+/// Registers this component's handlers, next to the standard handlers every component has
+///
+/// Add handlers for more component specific messages here!
+pub fn register_handlers(router: MessageRouter<HandlerContext>, requester: Requester) -> MessageRouter<HandlerContext> {
+    router
+        .on::<ExampleMessage, _>(ExampleMessageHandler)
+        .on::<AnotherExampleMessage, _>(AnotherExampleMessageHandler)
+        .on::<Envelope, _>(EnvelopeHandler { requester })
+}
+
+/// Runs the example's message exchange between components
+pub struct ExampleMessageHandler;
+
+#[async_trait]
+impl Handles<ExampleMessage, HandlerContext> for ExampleMessageHandler {
+    async fn handle(&mut self, example_message: ExampleMessage, context: &HandlerContext) -> HandlerResult {
+        log::debug!("{} has received an ExampleMessage", context.config.container.name);
+        // Process message using component's logic. This can be as complex as you like
+        let response: ExampleMessage = process_message(example_message, context.config.component.increment).await;
+        // Publish the response to every container subscribed to the example's topic
+        // Subscribers declare the topic in the <subscriptions> section of their configuration.xml
+        context.topics.publish(EXAMPLE_TOPIC, ContainerMessage::ExampleMessage(response))
+    }
+}
+
+pub struct AnotherExampleMessageHandler;
+
+#[async_trait]
+impl Handles<AnotherExampleMessage, HandlerContext> for AnotherExampleMessageHandler {
+    async fn handle(&mut self, _another_example_message: AnotherExampleMessage, context: &HandlerContext) -> HandlerResult {
+        log::debug!("{} has received AnotherExampleMessage - not logic implemented", context.config.container.name);
+        None
+    }
+}
+
+/// Answers requests from other containers, and hands replies to the requests awaiting them
+pub struct EnvelopeHandler {
+    requester: Requester
+}
+
+#[async_trait]
+impl Handles<Envelope, HandlerContext> for EnvelopeHandler {
+    async fn handle(&mut self, envelope: Envelope, context: &HandlerContext) -> HandlerResult {
+        let container_name = &context.config.container.name;
+        let Some(reply_to) = envelope.reply_to.clone() else {
+            if let Some(unclaimed) = self.requester.resolve(envelope) {
+                log::warn!("{} received a reply from {} that no request is waiting for", container_name, unclaimed.sender);
+            }
+            return None;
+        };
+
+        log::debug!("{} is answering a request from {}", container_name, envelope.sender);
+        let reply_payload = match envelope.payload.as_ref() {
+            ContainerMessage::ExampleMessage(example_message) => {
+                ContainerMessage::ExampleMessage(process_message(example_message.clone(), context.config.component.increment).await)
+            }
+
+            // Add more cases for requests your component can answer here!
+
+            other => {
+                log::debug!("{} cannot answer a request containing {:?}", container_name, other);
+                return None;
+            }
+        };
+        let reply = envelope.reply(container_name.clone(), reply_payload);
+        Some(ClientBrokerMessage::new(vec![reply_to.as_str()], ContainerMessage::Envelope(reply)))
+    }
+}
@@ -9,4 +9,5 @@
 // Example written by Robert Hannah 2025
 // -------------------------------------------------------------------------------------------------
 
+pub mod handlers;
 pub mod internal_logic;
@@ -11,6 +11,7 @@
 
 // Package
 use common_messages::messages::example_message::ExampleMessage;
+use common_messages::routing::container_id::ContainerId;
 use tokio::time::{sleep, Duration};
 
 
@@ -18,7 +19,7 @@ pub async fn process_message(message: ExampleMessage, increment_from_config: u8)
     log::info!("Processing ExampleMessage...  {:?}", message);
     sleep(Duration::from_secs(1)).await;
 
-    log::info!("Incrementing value and building new message to send to ComponentA");
+    log::info!("Incrementing value and building new message to send to {}", ContainerId::ComponentA);
     ExampleMessage::new(
         "Message from ComponentB!".into(),
         message.value + increment_from_config as u64
@@ -0,0 +1,429 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Local HTTP admin endpoint for probing and controlling a container from scripts and orchestrators.
+//!
+//! Enabled by the `<admin>` section of a component's configuration.xml. The endpoint speaks just enough
+//! HTTP/1.1 for health checks, Prometheus and `curl`, answering every request with JSON (or metrics) and closing
+//! the connection:
+//!
+//! * `GET /health` - 200 while the container is running, 503 once it is shutting down
+//! * `GET /ready` - 200 while the component is Active, 503 otherwise
+//! * `GET /info` - The container's `container_identity()`
+//! * `GET /peers` - The peers tracked by heartbeats, see [`crate::peers`]
+//! * `GET /metrics` - Message flow metrics in the Prometheus text format, see [`crate::metrics`]
+//! * `POST /component/<directive>` - Sends a `ComponentDirective`, e.g. `SetToDormant`, to the component
+//! * `POST /container/<directive>` - Sends a `ContainerDirective`, e.g. `Shutdown`, to the container
+//!
+//! The `POST` routes change the container's state, so they need the `<token>` configured in `<admin>` as an
+//! `Authorization: Bearer <token>` header, and are refused while no token is configured. Requests carrying an
+//! `Origin` header, which browsers add to cross-site requests, or a `Host` header other than the loopback interface
+//! or the configured address, which a DNS rebinding page would send, are refused on every route so a web page can't
+//! reach the endpoint. The `GET` routes aren't authenticated otherwise, and the token travels in plain text, so the
+//! endpoint should still only listen on the loopback interface or a trusted network.
+
+// Standard
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc as StdArc, Mutex};
+
+// Package
+use common_messages::messages::container_message::ContainerMessage;
+use hyperion_framework::containerisation::component_state::ComponentState;
+use hyperion_framework::containerisation::container_state::ContainerState;
+use hyperion_framework::containerisation::traits::ContainerIdentidy;
+use hyperion_framework::messages::component_directive::ComponentDirective;
+use hyperion_framework::messages::container_directive::ContainerDirective;
+use hyperion_framework::utilities::tx_sender::add_to_tx_with_retry;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::time::{timeout, Duration};
+
+// Local
+use crate::authentication::sign_directive;
+use crate::config_loader::load_xml;
+use crate::error::RuntimeError;
+use crate::metrics;
+use crate::peers::PeerMonitor;
+
+
+/// Address the admin endpoint listens on when the configuration doesn't say
+pub const DEFAULT_ADMIN_ADDRESS: &str = "127.0.0.1:50700";
+/// Largest request head read before the request is refused
+const MAX_REQUEST_HEAD: usize = 8192;
+/// How long a client may take to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// State of the running component, as last reported by it
+static COMPONENT_STATE: Mutex<Option<ComponentState>> = Mutex::new(None);
+
+/// The `<admin>` section of a component's configuration.xml
+#[derive(Debug, Clone, Deserialize)]
+pub struct AdminConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_address")]
+    pub address: String,
+    /// Bearer token the `POST` routes require; they are refused while it is empty
+    #[serde(default)]
+    pub token: String
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: default_address(),
+            token: String::new()
+        }
+    }
+}
+
+fn default_address() -> String {
+    DEFAULT_ADMIN_ADDRESS.to_string()
+}
+
+/// The parts of configuration.xml read by the runtime rather than the component
+#[derive(Debug, Default, Deserialize)]
+struct AdminSettings {
+    #[serde(default)]
+    admin: AdminConfig
+}
+
+/// What the endpoint answers from and sends directives into
+struct AdminContext {
+    config: AdminConfig,
+    main_tx: mpsc::Sender<ContainerMessage>,
+    container_state: StdArc<AtomicUsize>,
+    identity: BTreeMap<String, String>
+}
+
+/// Content type of the Prometheus text format
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// The parts of an admin request the endpoint looks at
+struct Request {
+    method: String,
+    path: String,
+    /// Header names in lower case, with their values
+    headers: Vec<(String, String)>
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(header, _)| header == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// A response to an admin request
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: String
+}
+
+impl Response {
+    fn new(status: u16, body: Value) -> Self {
+        Self { status, content_type: "application/json", body: body.to_string() }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::new(status, json!({ "error": message }))
+    }
+}
+
+/// Lets the admin endpoint's `/ready` know the component's state; call it whenever the state may have changed
+pub fn report_component_state(state: &ComponentState) {
+    *COMPONENT_STATE.lock().expect("component state lock poisoned") = Some(state.clone());
+}
+
+/// The component's state as last reported, None until the component has started
+pub fn component_state() -> Option<ComponentState> {
+    COMPONENT_STATE.lock().expect("component state lock poisoned").clone()
+}
+
+/// Starts serving the admin endpoint if it is enabled in the component's configuration
+///
+/// # Arguments
+///
+/// * `config_path` - The component's configuration.xml, also read for the container's identity
+/// * `main_tx` - The container's main channel, which directives are sent into
+/// * `container_state` - The container's state, for `/health`
+///
+/// # Returns
+///
+/// * `Result<bool, RuntimeError>` - Whether the endpoint is enabled
+pub async fn start_admin<Cfg>(config_path: &str, main_tx: mpsc::Sender<ContainerMessage>, container_state: StdArc<AtomicUsize>)
+    -> Result<bool, RuntimeError>
+where
+    Cfg: DeserializeOwned + ContainerIdentidy
+{
+    let settings = load_xml::<AdminSettings>(config_path)?;
+    if !settings.admin.enabled {
+        return Ok(false);
+    }
+    let identity = load_xml::<Cfg>(config_path)?.container_identity().into_iter().collect();
+    let listener = TcpListener::bind(&settings.admin.address).await
+        .map_err(|error| RuntimeError::Admin(format!("unable to listen on {}: {}", settings.admin.address, error)))?;
+    log::info!("The admin endpoint listens on http://{}", settings.admin.address);
+
+    if settings.admin.token.is_empty() {
+        log::warn!("The admin endpoint has no <token>, so its POST routes are disabled");
+    }
+    let context = StdArc::new(AdminContext { config: settings.admin, main_tx, container_state, identity });
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(serve_connection(stream, context.clone()));
+                }
+                Err(error) => log::warn!("Unable to accept an admin connection: {}", error)
+            }
+        }
+    });
+    Ok(true)
+}
+
+/// Reads one request from the connection and answers it
+async fn serve_connection(mut stream: TcpStream, context: StdArc<AdminContext>) {
+    let response = match timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(Some(request))) => {
+            log::debug!("Admin request: {} {}", request.method, request.path);
+            match refusal(&request, &context.config) {
+                Some(refusal) => {
+                    log::warn!("Refused the admin request {} {}: {}", request.method, request.path, refusal.body);
+                    refusal
+                }
+                None => handle_request(&request.method, &request.path, &context).await
+            }
+        }
+        Ok(Ok(None)) => Response::error(400, "malformed request"),
+        Ok(Err(error)) => {
+            log::debug!("Unable to read an admin request: {}", error);
+            return;
+        }
+        Err(_) => Response::error(408, "request timed out")
+    };
+    if let Err(error) = write_response(&mut stream, response).await {
+        log::debug!("Unable to answer an admin request: {}", error);
+    }
+}
+
+/// Reads the request head, or None if it isn't an HTTP request
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<Request>> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_HEAD {
+            return Ok(None);
+        }
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+    // Any request body is ignored, the directive is named by the path
+    Ok(parse_request_head(&String::from_utf8_lossy(&head)))
+}
+
+/// Parses the request line and headers of a request head
+fn parse_request_head(head: &str) -> Option<Request> {
+    let mut lines = head.lines();
+    let mut parts = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path), Some(version)) = (parts.next(), parts.next(), parts.next()) else {
+        return None;
+    };
+    if !version.starts_with("HTTP/") {
+        return None;
+    }
+    let headers = lines.take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    // The query string doesn't select anything
+    let path = path.split('?').next().unwrap_or_default();
+    Some(Request { method: method.to_string(), path: path.to_string(), headers })
+}
+
+/// Why a request is refused before it is handled, if it is
+fn refusal(request: &Request, config: &AdminConfig) -> Option<Response> {
+    if request.header("origin").is_some() {
+        return Some(Response::error(403, "requests from web pages are not accepted"));
+    }
+    // Browsers always send a Host header, other clients may leave it out
+    let host = request.header("host").unwrap_or("localhost");
+    if !is_allowed_host(host, &config.address) {
+        return Some(Response::error(403, &format!("host {} is not accepted", host)));
+    }
+    if request.method != "POST" {
+        return None;
+    }
+    if config.token.is_empty() {
+        return Some(Response::error(403, "POST requests need a <token> in the <admin> configuration"));
+    }
+    let token = request.header("authorization").and_then(|authorization| authorization.strip_prefix("Bearer "));
+    match token {
+        Some(token) if constant_time_eq(token.trim().as_bytes(), config.token.as_bytes()) => None,
+        _ => Some(Response::error(401, "a valid bearer token is required"))
+    }
+}
+
+/// Whether a `Host` header names the loopback interface or the address the endpoint listens on
+fn is_allowed_host(host: &str, address: &str) -> bool {
+    let host_name = |value: &str| -> String {
+        let value = value.trim().to_ascii_lowercase();
+        match value.strip_prefix('[') {
+            // [::1]:50700
+            Some(bracketed) => bracketed.split(']').next().unwrap_or_default().to_string(),
+            None => value.rsplit_once(':').map_or(value.clone(), |(name, _)| name.to_string())
+        }
+    };
+    let name = host_name(host);
+    matches!(name.as_str(), "localhost" | "127.0.0.1" | "::1") || name == host_name(address)
+}
+
+/// Compares two byte strings in time independent of where they differ
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len() && left.iter().zip(right).fold(0u8, |difference, (l, r)| difference | (l ^ r)) == 0
+}
+
+/// Answers a request
+async fn handle_request(method: &str, path: &str, context: &AdminContext) -> Response {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        ("GET", ["health"]) => {
+            if context.container_state.load(Ordering::SeqCst) == ContainerState::Running as usize {
+                Response::new(200, json!({ "status": "ok" }))
+            } else {
+                Response::new(503, json!({ "status": "shutting down" }))
+            }
+        }
+        ("GET", ["ready"]) => match component_state() {
+            Some(ComponentState::Active) => Response::new(200, json!({ "ready": true, "state": "Active" })),
+            Some(state) => Response::new(503, json!({ "ready": false, "state": format!("{:?}", state) })),
+            None => Response::new(503, json!({ "ready": false, "state": "Starting" }))
+        },
+        ("GET", ["info"]) => Response::new(200, json!(context.identity)),
+        ("GET", ["peers"]) => peers_response(),
+        ("GET", ["metrics"]) => Response { status: 200, content_type: METRICS_CONTENT_TYPE, body: metrics::render() },
+        ("POST", ["component", directive]) => match parse_directive::<ComponentDirective>(directive) {
+            Some(directive) => send_directive(context, ContainerMessage::ComponentDirectiveMsg(directive)).await,
+            None => Response::error(404, &format!("unknown component directive {}", directive))
+        },
+        ("POST", ["container", directive]) => match parse_directive::<ContainerDirective>(directive) {
+            Some(directive) => send_directive(context, ContainerMessage::ContainerDirectiveMsg(directive)).await,
+            None => Response::error(404, &format!("unknown container directive {}", directive))
+        },
+        (_, ["health" | "ready" | "info" | "peers" | "metrics"]) | (_, ["component" | "container", _]) =>
+            Response::error(405, &format!("{} is not allowed on {}", method, path)),
+        _ => Response::error(404, &format!("no such endpoint {}", path))
+    }
+}
+
+/// The peers tracked by heartbeats
+fn peers_response() -> Response {
+    let Some(monitor) = PeerMonitor::registered() else {
+        return Response::error(404, "heartbeats are disabled for this component");
+    };
+    let peers: Vec<Value> = monitor.peers().iter().map(|peer| json!({
+        "container": peer.container,
+        "state": peer.state,
+        "last_seen_ms_ago": peer.last_seen.map(|last_seen| last_seen.elapsed().as_millis() as u64),
+        "round_trip_ms": peer.round_trip.map(|round_trip| round_trip.as_millis() as u64)
+    })).collect();
+    Response::new(200, json!(peers))
+}
+
+/// Parses a directive named by its variant, e.g. `SetToDormant`
+fn parse_directive<D: DeserializeOwned>(name: &str) -> Option<D> {
+    serde_json::from_value(Value::String(name.to_string())).ok()
+}
+
+/// Sends a directive into the container's main channel, as the console would
+async fn send_directive(context: &AdminContext, message: ContainerMessage) -> Response {
+    let kind = format!("{:?}", message);
+    add_to_tx_with_retry(&context.main_tx, &sign_directive(message), "Admin endpoint", "Container main").await;
+    log::info!("The admin endpoint sent {}", kind);
+    Response::new(202, json!({ "sent": kind }))
+}
+
+async fn write_response(stream: &mut TcpStream, response: Response) -> std::io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        _ => "Service Unavailable"
+    };
+    let head = format!("HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status, reason, response.content_type, response.body.len());
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(token: &str) -> AdminConfig {
+        AdminConfig { enabled: true, address: "127.0.0.1:50700".to_string(), token: token.to_string() }
+    }
+
+    fn request(head: &str) -> Request {
+        parse_request_head(head).expect("a valid request head")
+    }
+
+    fn status(head: &str, config: &AdminConfig) -> Option<u16> {
+        refusal(&request(head), config).map(|response| response.status)
+    }
+
+    #[test]
+    fn the_request_head_is_parsed() {
+        let parsed = request("GET /ready?verbose=1 HTTP/1.1\r\nHost: localhost:50700\r\nX-Thing:  a:b \r\n\r\n");
+        assert_eq!(parsed.method, "GET");
+        assert_eq!(parsed.path, "/ready");
+        assert_eq!(parsed.header("host"), Some("localhost:50700"));
+        assert_eq!(parsed.header("x-thing"), Some("a:b"));
+        assert!(parse_request_head("hello").is_none());
+    }
+
+    #[test]
+    fn post_requests_need_the_configured_token() {
+        let post = "POST /container/Shutdown HTTP/1.1\r\nHost: 127.0.0.1:50700\r\n";
+        assert_eq!(status(&format!("{}Authorization: Bearer secret\r\n\r\n", post), &config("secret")), None);
+        assert_eq!(status(&format!("{}Authorization: Bearer wrong\r\n\r\n", post), &config("secret")), Some(401));
+        assert_eq!(status(&format!("{}\r\n", post), &config("secret")), Some(401));
+        // Without a configured token the POST routes are disabled
+        assert_eq!(status(&format!("{}Authorization: Bearer \r\n\r\n", post), &config("")), Some(403));
+        assert_eq!(status("GET /health HTTP/1.1\r\nHost: 127.0.0.1:50700\r\n\r\n", &config("")), None);
+    }
+
+    #[test]
+    fn requests_from_web_pages_are_refused() {
+        let origin = "POST /container/Shutdown HTTP/1.1\r\nHost: 127.0.0.1:50700\r\nOrigin: http://evil.example\r\n\
+            Authorization: Bearer secret\r\n\r\n";
+        assert_eq!(status(origin, &config("secret")), Some(403));
+        let rebound = "GET /info HTTP/1.1\r\nHost: evil.example:50700\r\n\r\n";
+        assert_eq!(status(rebound, &config("secret")), Some(403));
+        assert_eq!(status("GET /info HTTP/1.1\r\nHost: [::1]:50700\r\n\r\n", &config("")), None);
+    }
+}
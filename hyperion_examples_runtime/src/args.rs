@@ -0,0 +1,212 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! Command line arguments shared by every component binary.
+
+// Local
+use crate::error::RuntimeError;
+
+
+/// Environment variable overriding the container configuration file
+pub const CONFIG_ENV_VAR: &str = "HYPERION_CONFIG";
+/// Environment variable overriding the network topology file
+pub const TOPOLOGY_ENV_VAR: &str = "HYPERION_TOPOLOGY";
+/// Environment variable setting the instance id
+pub const INSTANCE_ENV_VAR: &str = "HYPERION_INSTANCE";
+/// Environment variable setting the instance port offset
+pub const PORT_OFFSET_ENV_VAR: &str = "HYPERION_PORT_OFFSET";
+
+/// Usage text printed for `--help`
+pub const USAGE: &str = "\
+Options:
+  --config <path>     Container configuration file (env: HYPERION_CONFIG)
+  --topology <path>   Network topology file (env: HYPERION_TOPOLOGY)
+  --instance <id>     Instance id substituted into templated config files (env: HYPERION_INSTANCE)
+  --port-offset <n>   Added to templated ports, defaults to a numeric instance id (env: HYPERION_PORT_OFFSET)
+  --daemon            Run without the stdin console, controlled by signals
+  --help              Show this help";
+
+/// Options given when launching a component binary
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LaunchArgs {
+    pub config_path: Option<String>,
+    pub topology_path: Option<String>,
+    pub instance: Option<String>,
+    pub port_offset: Option<u16>,
+    pub daemon: bool,
+    pub help: bool
+}
+
+impl LaunchArgs {
+    /// Parses the arguments of the running process
+    pub fn from_env() -> Result<LaunchArgs, RuntimeError> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Parses arguments, excluding the program name
+    ///
+    /// Values can be given as `--config <path>` or `--config=<path>`.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<LaunchArgs, RuntimeError> {
+        let mut launch_args = LaunchArgs::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None)
+            };
+            match flag.as_str() {
+                "--config" => launch_args.config_path = Some(flag_value(&flag, inline_value, &mut args)?),
+                "--topology" => launch_args.topology_path = Some(flag_value(&flag, inline_value, &mut args)?),
+                "--instance" => launch_args.instance = Some(flag_value(&flag, inline_value, &mut args)?),
+                "--port-offset" => launch_args.port_offset = Some(parse_port_offset(&flag_value(&flag, inline_value, &mut args)?)?),
+                "--daemon" => launch_args.daemon = true,
+                "--help" | "-h" => launch_args.help = true,
+                _ => return Err(RuntimeError::InvalidArgument(format!("unknown argument `{}`", flag)))
+            }
+        }
+        Ok(launch_args)
+    }
+
+    /// Fills instance options not given on the command line from the environment
+    pub fn with_env_instance(mut self) -> Result<LaunchArgs, RuntimeError> {
+        if self.instance.is_none() {
+            self.instance = non_empty_env(INSTANCE_ENV_VAR);
+        }
+        if self.port_offset.is_none() {
+            self.port_offset = non_empty_env(PORT_OFFSET_ENV_VAR)
+                .map(|offset| parse_port_offset(&offset))
+                .transpose()?;
+        }
+        Ok(self)
+    }
+}
+
+/// Reads an environment variable, treating an empty value as unset
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Parses a port offset value
+fn parse_port_offset(value: &str) -> Result<u16, RuntimeError> {
+    value.parse().map_err(|_| RuntimeError::InvalidArgument(format!("`{}` is not a valid port offset", value)))
+}
+
+/// Takes the value of a flag, either given inline or as the next argument
+fn flag_value<I: Iterator<Item = String>>(flag: &str, inline_value: Option<String>, args: &mut I) -> Result<String, RuntimeError> {
+    inline_value
+        .or_else(|| args.next())
+        .filter(|value| !value.is_empty() && !value.starts_with("--"))
+        .ok_or_else(|| RuntimeError::InvalidArgument(format!("`{}` needs a value", flag)))
+}
+
+/// Resolves a configuration file path, preferring the command line, then the environment, then the default
+///
+/// # Returns
+///
+/// * `(String, String)` - The path and a description of where it came from, for error messages
+pub fn resolve_path(arg: Option<&str>, flag: &str, env_var: &str, default: &str) -> (String, String) {
+    if let Some(path) = arg {
+        return (path.to_string(), flag.to_string());
+    }
+    match non_empty_env(env_var) {
+        Some(path) => (path, env_var.to_string()),
+        None => (default.to_string(), "the component default".to_string())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<LaunchArgs, RuntimeError> {
+        LaunchArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse(args) {
+            Ok(launch_args) => panic!("{:?} was accepted as {:?}", args, launch_args),
+            Err(error) => error.to_string()
+        }
+    }
+
+    #[test]
+    fn no_arguments_leave_the_defaults() {
+        assert_eq!(parse(&[]).unwrap(), LaunchArgs::default());
+    }
+
+    #[test]
+    fn values_are_given_separately_or_inline() {
+        let launch_args = parse(&["--config", "b/configuration.xml", "--topology=b/network_topology.xml",
+            "--instance=1", "--port-offset", "10", "--daemon"]).unwrap();
+        assert_eq!(launch_args, LaunchArgs {
+            config_path: Some("b/configuration.xml".to_string()),
+            topology_path: Some("b/network_topology.xml".to_string()),
+            instance: Some("1".to_string()),
+            port_offset: Some(10),
+            daemon: true,
+            help: false
+        });
+        assert!(parse(&["-h"]).unwrap().help);
+        assert!(parse(&["--help"]).unwrap().help);
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        assert!(error(&["--verbose"]).contains("unknown argument `--verbose`"));
+        assert!(error(&["--config"]).contains("`--config` needs a value"));
+        assert!(error(&["--config="]).contains("`--config` needs a value"));
+        assert!(error(&["--config", "--daemon"]).contains("`--config` needs a value"));
+        assert!(error(&["--port-offset", "-1"]).contains("`-1` is not a valid port offset"));
+        assert!(error(&["--port-offset=70000"]).contains("`70000` is not a valid port offset"));
+    }
+
+    #[test]
+    fn the_command_line_wins_over_the_default() {
+        let unset = "HYPERION_ARGS_TEST_UNSET";
+        assert_eq!(resolve_path(Some("given.xml"), "--config", unset, "default.xml"),
+            ("given.xml".to_string(), "--config".to_string()));
+        assert_eq!(resolve_path(None, "--config", unset, "default.xml"),
+            ("default.xml".to_string(), "the component default".to_string()));
+    }
+
+    #[test]
+    fn the_command_line_wins_over_the_environment() {
+        let var = "HYPERION_ARGS_TEST_CONFIG";
+        // SAFETY: the variable is only read by this test
+        unsafe { std::env::set_var(var, "from_env.xml") };
+        assert_eq!(resolve_path(Some("given.xml"), "--config", var, "default.xml"),
+            ("given.xml".to_string(), "--config".to_string()));
+        assert_eq!(resolve_path(None, "--config", var, "default.xml"),
+            ("from_env.xml".to_string(), var.to_string()));
+        unsafe { std::env::set_var(var, "") };
+        assert_eq!(resolve_path(None, "--config", var, "default.xml"),
+            ("default.xml".to_string(), "the component default".to_string()));
+    }
+
+    #[test]
+    fn instance_options_fall_back_to_the_environment() {
+        // SAFETY: no other test reads the instance variables
+        unsafe {
+            std::env::set_var(INSTANCE_ENV_VAR, "7");
+            std::env::set_var(PORT_OFFSET_ENV_VAR, "70");
+        }
+        let given = parse(&["--instance", "2", "--port-offset", "20"]).unwrap().with_env_instance().unwrap();
+        assert_eq!((given.instance.as_deref(), given.port_offset), (Some("2"), Some(20)));
+        let from_env = parse(&[]).unwrap().with_env_instance().unwrap();
+        assert_eq!((from_env.instance.as_deref(), from_env.port_offset), (Some("7"), Some(70)));
+        unsafe {
+            std::env::remove_var(INSTANCE_ENV_VAR);
+            std::env::remove_var(PORT_OFFSET_ENV_VAR);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Authentication of the messages between containers, with a key pair per container.
+//!
+//! Reads the `<authentication>` section of a component's configuration.xml, loads the container's private key and
+//! the public keys of its peers from the key directory and installs them before the Hyperion container is created,
+//! so no message is sent or accepted unsigned. The signing itself is part of the message wire format, see
+//! [`common_messages::messages::signed`]. Messages are signed where they leave the container: everything the
+//! component sends passes through [`sign_outgoing`], and container directives from the console, signals or admin
+//! endpoint are signed with [`sign_directive`] before Hyperion passes them on. Keys for local testing can be generated with
+//! `cargo run --bin generate_keys`.
+
+// Standard
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// Package
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::messages::signed::{generate_key_pair, parse_public_key, MessageAuth};
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+use hyperion_framework::utilities::tx_sender::add_to_tx_with_retry;
+use serde::Deserialize;
+use tokio::sync::mpsc::{self, Sender};
+use tokio::task;
+use tokio::time::Duration;
+
+// Local
+use crate::config_loader::load_xml;
+use crate::error::RuntimeError;
+
+
+/// How old a received message may be when the configuration doesn't say
+pub const DEFAULT_MAX_AGE_MS: u64 = 30_000;
+/// Where the keys are read from when the configuration doesn't say
+pub const DEFAULT_KEY_DIRECTORY: &str = "keys";
+/// Extension of a container's private key, a PKCS#8 document
+pub const PRIVATE_KEY_EXTENSION: &str = "pk8";
+/// Extension of a container's public key, hex encoded
+pub const PUBLIC_KEY_EXTENSION: &str = "pub";
+
+/// The `<authentication>` section of a component's configuration.xml
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthenticationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Holds `<container name>.pk8` with this container's private key and a `<container name>.pub` for every
+    /// container whose messages are accepted
+    #[serde(default = "default_key_directory")]
+    pub key_directory: String,
+    /// How far a received message's timestamp may be from the local clock before it is rejected as stale
+    #[serde(default = "default_max_age_ms")]
+    pub max_age_ms: u64,
+    /// Containers allowed to send container and component directives, one `<directive_sender>` each
+    #[serde(default)]
+    pub directive_sender: Vec<String>
+}
+
+impl Default for AuthenticationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key_directory: DEFAULT_KEY_DIRECTORY.to_string(),
+            max_age_ms: DEFAULT_MAX_AGE_MS,
+            directive_sender: Vec::new()
+        }
+    }
+}
+
+fn default_max_age_ms() -> u64 {
+    DEFAULT_MAX_AGE_MS
+}
+
+fn default_key_directory() -> String {
+    DEFAULT_KEY_DIRECTORY.to_string()
+}
+
+/// The parts of configuration.xml read by the runtime rather than the component
+#[derive(Debug, Default, Deserialize)]
+struct AuthenticationSettings {
+    #[serde(default)]
+    authentication: AuthenticationConfig
+}
+
+/// Signs and checks the messages of this process if authentication is enabled in its configuration
+///
+/// The keys can't change while the container runs, so changes to `<authentication>` or the key directory need a
+/// restart.
+///
+/// # Arguments
+///
+/// * `config_path` - The component's configuration.xml
+/// * `container_name` - Name this container's messages are signed as
+///
+/// # Returns
+///
+/// * `Result<bool, RuntimeError>` - Whether authentication is enabled
+pub fn enable_authentication(config_path: &str, container_name: &str) -> Result<bool, RuntimeError> {
+    let config = load_xml::<AuthenticationSettings>(config_path)?.authentication;
+    if !config.enabled {
+        return Ok(false);
+    }
+
+    let directory = Path::new(&config.key_directory);
+    let private_key_path = directory.join(format!("{}.{}", container_name, PRIVATE_KEY_EXTENSION));
+    let private_key = fs::read(&private_key_path)
+        .map_err(|source| RuntimeError::Io { path: private_key_path.display().to_string(), source })?;
+    let peer_keys = load_public_keys(directory)?;
+    let auth = MessageAuth::new(container_name.to_string(), &private_key, peer_keys,
+        Duration::from_millis(config.max_age_ms), config.directive_sender.clone())
+        .map_err(|message| RuntimeError::Parse { path: private_key_path.display().to_string(), message })?;
+    if !auth.install() {
+        log::warn!("Message authentication was already set up, {} is not applied again", config_path);
+    }
+    log::info!("{} signs its messages and only accepts signed ones; directives are accepted from: {}", container_name,
+        if config.directive_sender.is_empty() { "no container".to_string() } else { config.directive_sender.join(", ") });
+    Ok(true)
+}
+
+/// Signs every message the component sends before handing it to Hyperion
+///
+/// Without authentication the messages are sent as they are and `comp_out_tx` is returned unchanged.
+///
+/// # Arguments
+///
+/// * `comp_out_tx` - The component's channel to the Hyperion container
+/// * `container_name` - Name of this container, for logging
+///
+/// # Returns
+///
+/// * `Sender<ClientBrokerMessage<ContainerMessage>>` - The channel the component sends its messages into instead
+pub fn sign_outgoing(comp_out_tx: Sender<ClientBrokerMessage<ContainerMessage>>, container_name: &str)
+    -> Sender<ClientBrokerMessage<ContainerMessage>> {
+    let Some(auth) = MessageAuth::installed() else {
+        return comp_out_tx;
+    };
+    let (signing_tx, mut signing_rx) = mpsc::channel::<ClientBrokerMessage<ContainerMessage>>(comp_out_tx.max_capacity());
+    let from_location = format!("{} message signing", container_name);
+    let to_location = format!("{} Container", container_name);
+    task::spawn(async move {
+        while let Some(broker_message) = signing_rx.recv().await {
+            let signed = ClientBrokerMessage {
+                client_name_list: broker_message.client_name_list,
+                message: broker_message.message.signed_by(auth)
+            };
+            add_to_tx_with_retry(&comp_out_tx, &signed, &from_location, &to_location).await;
+        }
+    });
+    signing_tx
+}
+
+/// Signs a container directive raised within this container, so Hyperion can pass it on to other containers
+///
+/// Other messages, and every message without authentication, are returned as they are.
+pub fn sign_directive(message: ContainerMessage) -> ContainerMessage {
+    match (MessageAuth::installed(), &message) {
+        (Some(auth), ContainerMessage::ContainerDirectiveMsg(_)) => message.signed_by(auth),
+        _ => message
+    }
+}
+
+/// Reads every `<container name>.pub` file of the key directory
+///
+/// # Returns
+///
+/// * `Result<HashMap<String, Vec<u8>>, RuntimeError>` - The public keys by container name
+pub fn load_public_keys(directory: &Path) -> Result<HashMap<String, Vec<u8>>, RuntimeError> {
+    let read_error = |source| RuntimeError::Io { path: directory.display().to_string(), source };
+    let mut keys = HashMap::new();
+    for entry in fs::read_dir(directory).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some(PUBLIC_KEY_EXTENSION) {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let contents = fs::read_to_string(&path)
+            .map_err(|source| RuntimeError::Io { path: path.display().to_string(), source })?;
+        let key = parse_public_key(&contents).ok_or_else(|| RuntimeError::Parse {
+            path: path.display().to_string(),
+            message: "not a hex encoded Ed25519 public key".to_string()
+        })?;
+        keys.insert(name.to_string(), key);
+    }
+    Ok(keys)
+}
+
+/// Writes a new key pair for each container into the key directory
+///
+/// # Arguments
+///
+/// * `directory` - The key directory, created if it doesn't exist
+/// * `names` - Container names to generate keys for
+pub fn generate_keys(directory: &Path, names: &[String]) -> Result<(), RuntimeError> {
+    fs::create_dir_all(directory)
+        .map_err(|source| RuntimeError::Write { path: directory.display().to_string(), source })?;
+    for name in names {
+        let (private_key, public_key) = generate_key_pair()
+            .map_err(|message| RuntimeError::Parse { path: directory.display().to_string(), message })?;
+        write_private_key(&directory.join(format!("{}.{}", name, PRIVATE_KEY_EXTENSION)), &private_key)?;
+        let public_key_path = directory.join(format!("{}.{}", name, PUBLIC_KEY_EXTENSION));
+        fs::write(&public_key_path, format!("{}\n", public_key))
+            .map_err(|source| RuntimeError::Write { path: public_key_path.display().to_string(), source })?;
+    }
+    Ok(())
+}
+
+/// Writes a private key readable by its owner only
+fn write_private_key(path: &Path, key: &[u8]) -> Result<(), RuntimeError> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let write = || -> std::io::Result<()> {
+        use std::io::Write;
+        options.open(path)?.write_all(key)
+    };
+    write().map_err(|source| RuntimeError::Write { path: path.display().to_string(), source })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_keys_are_loaded_by_container_name() {
+        let directory = std::env::temp_dir().join(format!("hyperion_keys_test_{}", std::process::id()));
+        let names = vec!["ComponentA".to_string(), "ComponentB".to_string()];
+        generate_keys(&directory, &names).unwrap();
+
+        let keys = load_public_keys(&directory).unwrap();
+        assert_eq!(keys.len(), 2);
+        let private_key = fs::read(directory.join("ComponentA.pk8")).unwrap();
+        assert!(MessageAuth::new("ComponentA".to_string(), &private_key, keys, Duration::from_secs(30), Vec::new()).is_ok());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Generates an Ed25519 key pair per container for signing the messages between them.
+//!
+//! ```text
+//! cargo run --bin generate_keys -- [<directory>] [<container name>...]
+//! ```
+//!
+//! Writes `<name>.pk8` (private, PKCS#8) and `<name>.pub` (public, hex) for every container into the directory
+//! (`keys` by default). Without names, keys are generated for every known container. Each container needs its
+//! own `.pk8` and the `.pub` of every container it accepts messages from.
+
+// Standard
+use std::env;
+use std::path::Path;
+use std::process::ExitCode;
+
+// Package
+use common_messages::routing::container_id::ContainerId;
+use hyperion_examples_runtime::authentication::{generate_keys, DEFAULT_KEY_DIRECTORY};
+
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let directory = args.next().unwrap_or_else(|| DEFAULT_KEY_DIRECTORY.to_string());
+    let mut names: Vec<String> = args.collect();
+    if names.is_empty() {
+        names = ContainerId::ALL.iter().map(|id| id.as_str().to_string()).collect();
+    }
+
+    match generate_keys(Path::new(&directory), &names) {
+        Ok(()) => {
+            println!("Wrote the keys for {} to {}", names.join(", "), directory);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("Unable to generate the keys: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Generates a certificate authority and a certificate per container for trying out TLS locally.
+//!
+//! ```text
+//! cargo run --features test-certs --bin generate_test_certs -- [<directory>] [<container name>...]
+//! ```
+//!
+//! Writes `ca.pem` and, for every container, `<name>.pem` and `<name>.key` into the directory (`certs` by
+//! default). Without names, certificates are generated for every known container. The certificates and keys are
+//! only meant for testing.
+
+// Standard
+use std::env;
+use std::path::Path;
+use std::process::ExitCode;
+
+// Package
+use common_messages::routing::container_id::ContainerId;
+use hyperion_examples_runtime::test_certs::generate_test_certs;
+
+
+/// Directory the certificates are written to when none is given
+const DEFAULT_DIRECTORY: &str = "certs";
+
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let directory = args.next().unwrap_or_else(|| DEFAULT_DIRECTORY.to_string());
+    let mut names: Vec<String> = args.collect();
+    if names.is_empty() {
+        names = ContainerId::ALL.iter().map(|id| id.as_str().to_string()).collect();
+    }
+
+    match generate_test_certs(Path::new(&directory), &names) {
+        Ok(()) => {
+            println!("Wrote the test certificates for {} to {}", names.join(", "), directory);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("Unable to generate the test certificates: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
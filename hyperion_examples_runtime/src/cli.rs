@@ -0,0 +1,352 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! Command registry for the container console.
+//!
+//! Components register named commands with a description, an optional argument parser and a
+//! closure producing the [`ContainerMessage`] to send. The help text and the "unknown command"
+//! output are generated from the registry, so they can't drift from the commands that exist.
+
+// Package
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::messages::forward_message::ForwardMessage;
+use common_messages::routing::container_id::ContainerId;
+use hyperion_framework::messages::container_directive::ContainerDirective;
+use hyperion_framework::messages::component_directive::ComponentDirective;
+
+// Local
+use crate::outbound;
+use crate::peers;
+
+
+/// Name of the built-in help command
+pub const HELP_COMMAND: &str = "h";
+
+/// Flag of the `send` command naming the containers a message is forwarded to
+const SEND_TARGET_FLAG: &str = "--to";
+
+/// Width of the command column in the help text
+const NAME_COLUMN_WIDTH: usize = 14;
+
+/// Turns the argument text following a command name into the console's reaction
+type CommandHandler = Box<dyn Fn(&str) -> Result<CommandOutcome, String> + Send + Sync>;
+
+/// What the console should do after a line has been dispatched
+#[derive(Debug)]
+pub enum CommandOutcome {
+    /// Send the message into the container's main channel
+    Send(ContainerMessage),
+    /// Print the text to the console without contacting the container
+    Print(String)
+}
+
+/// A single named console command
+pub struct CliCommand {
+    name: String,
+    description: String,
+    usage: String,
+    handler: CommandHandler
+}
+
+impl CliCommand {
+    /// Creates a command that takes no arguments and sends the produced message
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Text typed into the console to trigger the command
+    /// * `description` - Shown next to the command in the help text
+    /// * `message` - Builds the message sent to the container each time the command is entered
+    pub fn new<F>(name: &str, description: &str, message: F) -> Self
+    where
+        F: Fn() -> ContainerMessage + Send + Sync + 'static
+    {
+        let command_name = name.to_string();
+        Self::from_handler(name, description, "", move |args| {
+            if !args.is_empty() {
+                return Err(format!("`{}` does not take arguments", command_name));
+            }
+            Ok(CommandOutcome::Send(message()))
+        })
+    }
+
+    /// Creates a command whose arguments are parsed before the message is produced
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Text typed into the console to trigger the command
+    /// * `description` - Shown next to the command in the help text
+    /// * `usage` - Argument synopsis shown in the help text, e.g. `<value>`
+    /// * `parser` - Parses the text following the command name
+    /// * `message` - Builds the message sent to the container from the parsed arguments
+    pub fn with_args<A, P, F>(name: &str, description: &str, usage: &str, parser: P, message: F) -> Self
+    where
+        P: Fn(&str) -> Result<A, String> + Send + Sync + 'static,
+        F: Fn(A) -> ContainerMessage + Send + Sync + 'static
+    {
+        Self::from_handler(name, description, usage, move |args| {
+            parser(args).map(|parsed| CommandOutcome::Send(message(parsed)))
+        })
+    }
+
+    /// Creates a command with full control over the console's reaction
+    pub fn from_handler<H>(name: &str, description: &str, usage: &str, handler: H) -> Self
+    where
+        H: Fn(&str) -> Result<CommandOutcome, String> + Send + Sync + 'static
+    {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            usage: usage.to_string(),
+            handler: Box::new(handler)
+        }
+    }
+
+    /// Name typed into the console to trigger the command
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Help text line for this command
+    fn help_line(&self) -> String {
+        let synopsis = if self.usage.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.name, self.usage)
+        };
+        if synopsis.len() < NAME_COLUMN_WIDTH {
+            format!("{:<width$}- {}", synopsis, self.description, width = NAME_COLUMN_WIDTH)
+        } else {
+            // Long synopses get their own line so the descriptions stay aligned
+            format!("{}\n{:<width$}- {}", synopsis, "", self.description, width = NAME_COLUMN_WIDTH)
+        }
+    }
+}
+
+/// The set of commands understood by a container console
+pub struct CommandRegistry {
+    commands: Vec<CliCommand>
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+impl CommandRegistry {
+    /// Creates a registry without any commands besides help
+    pub fn new() -> Self {
+        Self { commands: Vec::new() }
+    }
+
+    /// Creates a registry holding the lifecycle commands every container supports
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        // Component state management
+        registry.register(CliCommand::new("start", "Component state set to active",
+            || ContainerMessage::ComponentDirectiveMsg(ComponentDirective::SetToActive)));
+        registry.register(CliCommand::new("suspend", "Component state set to dormant",
+            || ContainerMessage::ComponentDirectiveMsg(ComponentDirective::SetToDormant)));
+        registry.register(CliCommand::new("snapshot", "Write the component state to a snapshot file",
+            || ContainerMessage::ComponentDirectiveMsg(ComponentDirective::WriteToFile)));
+        // Container management
+        registry.register(CliCommand::new("s", "Graceful container shutdown",
+            || ContainerMessage::ContainerDirectiveMsg(ContainerDirective::Shutdown)));
+        registry.register(CliCommand::new("s.", "Graceful container network shutdown",
+            || ContainerMessage::ContainerDirectiveMsg(ContainerDirective::SystemShutdown)));
+        // Messages waiting for containers that are down
+        registry.register(CliCommand::from_handler("queues", "Show the outbound queues for unreachable containers", "",
+            |_| Ok(CommandOutcome::Print(outbound::status_text()))));
+        // Liveness of the containers this one connects to
+        registry.register(CliCommand::from_handler("peers", "Show the state of the connected containers", "",
+            |_| Ok(CommandOutcome::Print(peers::status_text()))));
+        // Arbitrary messages, mainly for poking live services while debugging
+        registry.register(CliCommand::with_args("send", "Send any ContainerMessage, optionally on to other containers",
+            "[--to <name>[,<name>]] <Variant> <json>", SendArgs::parse, SendArgs::into_message));
+        registry
+    }
+
+    /// Adds a command, replacing any existing command with the same name
+    pub fn register(&mut self, command: CliCommand) -> &mut Self {
+        if command.name == HELP_COMMAND {
+            log::warn!("Console command `{}` is reserved for help and was not registered", HELP_COMMAND);
+            return self;
+        }
+        if let Some(existing) = self.commands.iter_mut().find(|existing| existing.name == command.name) {
+            log::debug!("Console command `{}` has been replaced", command.name);
+            *existing = command;
+        } else {
+            self.commands.push(command);
+        }
+        self
+    }
+
+    /// Help text listing every registered command
+    pub fn help_text(&self) -> String {
+        let mut help = String::from("Commands:");
+        help.push_str(&format!("\n{:<width$}- Show this help", HELP_COMMAND, width = NAME_COLUMN_WIDTH));
+        for command in &self.commands {
+            help.push('\n');
+            help.push_str(&command.help_line());
+        }
+        help
+    }
+
+    /// Resolves a line typed into the console
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The command name, optionally followed by whitespace and its arguments
+    ///
+    /// # Returns
+    ///
+    /// * `CommandOutcome` - The message to send, or the text to print for help and errors
+    pub fn dispatch(&self, line: &str) -> CommandOutcome {
+        let line = line.trim();
+        let (name, args) = split_first_word(line);
+
+        if name == HELP_COMMAND {
+            return CommandOutcome::Print(self.help_text());
+        }
+        match self.commands.iter().find(|command| command.name == name) {
+            Some(command) => (command.handler)(args).unwrap_or_else(|error| {
+                CommandOutcome::Print(format!("Invalid use of `{}`: {}\nUsage: {}", name, error, command.help_line()))
+            }),
+            None => CommandOutcome::Print(format!("Unknown command: {}\n\n\n{}", line, self.help_text()))
+        }
+    }
+}
+
+/// Arguments of the built-in `send` command
+#[derive(Debug)]
+pub struct SendArgs {
+    /// Containers the message is forwarded to; empty to deliver it to this container's component
+    pub targets: Vec<String>,
+    pub message: ContainerMessage
+}
+
+impl SendArgs {
+    /// Parses `[--to <name>[,<name>]] <Variant> <json>`
+    ///
+    /// The payload is decoded as the given `ContainerMessage` variant, so any message type can be sent
+    /// without a dedicated command, e.g. `send --to ComponentB ExampleMessage {"message":"x","value":41}`.
+    pub fn parse(args: &str) -> Result<SendArgs, String> {
+        let mut rest = args.trim();
+        let mut targets = Vec::new();
+
+        let (first, remainder) = split_first_word(rest);
+        if first == SEND_TARGET_FLAG {
+            let (target_list, remainder) = split_first_word(remainder);
+            targets = target_list.split(',')
+                .filter(|target| !target.is_empty())
+                .map(String::from)
+                .collect();
+            if targets.is_empty() {
+                return Err(format!("`{}` needs at least one container name", SEND_TARGET_FLAG));
+            }
+            // Names stay strings so an instance of a container, e.g. ComponentB-1, can be addressed
+            for target in &targets {
+                ContainerId::of_container(target)?;
+            }
+            rest = remainder;
+        }
+
+        let (variant, payload) = split_first_word(rest);
+        if variant.is_empty() {
+            return Err("expected a ContainerMessage variant".to_string());
+        }
+        if payload.is_empty() {
+            return Err(format!("expected a JSON payload for {}", variant));
+        }
+        let payload: serde_json::Value = serde_json::from_str(payload)
+            .map_err(|error| format!("invalid JSON payload: {}", error))?;
+        // Typed into this container's console, so there is no signature to check
+        let wire = serde_json::json!({ "type": variant, "payload": payload });
+        let message = match ContainerMessage::from_local(wire) {
+            ContainerMessage::UnknownMessage(unknown) => return Err(unknown.reason),
+            message => message
+        };
+
+        Ok(SendArgs { targets, message })
+    }
+
+    /// Builds the message for the container's main channel, wrapping it for forwarding if targets were given
+    pub fn into_message(self) -> ContainerMessage {
+        if self.targets.is_empty() {
+            self.message
+        } else {
+            ContainerMessage::ForwardMessage(ForwardMessage::new(self.targets, self.message))
+        }
+    }
+}
+
+/// Splits off the first whitespace separated word, returning it and the trimmed remainder
+fn split_first_word(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    match text.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (text, "")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_message_without_targets_is_for_this_component() {
+        let args = SendArgs::parse(r#"ExampleMessage {"message":"x","value":41}"#).unwrap();
+        assert!(args.targets.is_empty());
+        match args.into_message() {
+            ContainerMessage::ExampleMessage(example) => assert_eq!(example.value, 41),
+            other => panic!("expected an ExampleMessage, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn a_message_with_targets_is_forwarded() {
+        let args = SendArgs::parse(r#"  --to ComponentB,,ComponentB-1   ExampleMessage  {"message": "x", "value": 1} "#).unwrap();
+        assert_eq!(args.targets, vec!["ComponentB".to_string(), "ComponentB-1".to_string()]);
+        match args.into_message() {
+            ContainerMessage::ForwardMessage(forward) => {
+                assert_eq!(forward.targets, vec!["ComponentB".to_string(), "ComponentB-1".to_string()]);
+                assert!(matches!(*forward.message, ContainerMessage::ExampleMessage(_)));
+            }
+            other => panic!("expected a ForwardMessage, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn local_only_messages_can_be_sent_from_the_console() {
+        let args = SendArgs::parse(r#"ConfigReloadMessage {"config_path":"configuration.xml"}"#).unwrap();
+        assert!(matches!(args.message, ContainerMessage::ConfigReloadMessage(_)));
+    }
+
+    #[test]
+    fn invalid_send_arguments_are_rejected() {
+        for (args, error) in [
+            ("", "expected a ContainerMessage variant"),
+            ("--to", "`--to` needs at least one container name"),
+            ("--to , ExampleMessage {}", "`--to` needs at least one container name"),
+            (r#"--to ComponentB,ComponentC ExampleMessage {"message":"x","value":1}"#, "unknown container `ComponentC`"),
+            ("ExampleMessage", "expected a JSON payload for ExampleMessage"),
+            ("ExampleMessage {value:1}", "invalid JSON payload"),
+            (r#"NoSuchMessage {"value":1}"#, "unknown message type `NoSuchMessage`"),
+            (r#"ExampleMessage {"value":"x"}"#, "invalid type")
+        ] {
+            match SendArgs::parse(args) {
+                Ok(parsed) => panic!("`{}` was accepted as {:?}", args, parsed),
+                Err(message) => assert!(message.contains(error), "`{}` failed with `{}`", args, message)
+            }
+        }
+    }
+}
@@ -0,0 +1,553 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! The component every example container runs.
+//!
+//! Delivery, Dormant handling, snapshots, persistence, reliable delivery and tracing are the same for every
+//! example component, so they live here. A component only adds what is specific to it through [`ComponentLogic`]:
+//! the containers it sends messages to and the handlers of its own messages.
+
+// Standard
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc as StdArc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+// Package
+use common_messages::messages::container_message::{local, ContainerMessage};
+use common_messages::messages::example_message::ExampleMessage;
+use common_messages::routing::container_id::ContainerId;
+use common_messages::routing::topic::EXAMPLE_TOPIC;
+use hyperion_framework::containerisation::traits::ContainerIdentidy;
+use hyperion_framework::containerisation::traits::{Initialisable, Run};
+use hyperion_framework::containerisation::component_state::ComponentState;
+use hyperion_framework::containerisation::container_state::ContainerState;
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+use hyperion_framework::messages::component_directive::ComponentDirective;
+use hyperion_framework::utilities::tx_sender::add_to_tx_with_retry;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{Sender, Receiver};
+use tokio::sync::Notify;
+use tracing::Instrument;
+
+// Local
+use crate::admin::report_component_state;
+use crate::authentication::sign_outgoing;
+use crate::config::Config;
+use crate::dormant::{is_business_message, DormantGate};
+use crate::handlers::StandardHandlers;
+use crate::handshake::hello_message;
+use crate::metrics::{self, Direction};
+use crate::outbound::OutboundQueues;
+use crate::peers::{next_peer_status, PeerMonitor};
+use crate::persistence::{PersistentState, StateLog};
+use crate::reliable::{ReliableReceiver, ReliableSender};
+use crate::request::Requester;
+use crate::router::MessageRouter;
+use crate::routing::RoutingTargets;
+use crate::topics::TopicRegistry;
+use crate::snapshot::{latest_snapshot, write_snapshot, MessageHistory, Snapshot};
+use crate::trace;
+
+
+/// Messages the component deals with itself rather than through its message router
+///
+/// Signed messages are opened as they are received, so only the message they carry reaches the router.
+pub const HANDLED_BY_COMPONENT: &[&str] = &["ContainerDirectiveMsg", "ComponentDirectiveMsg", "ConfigReloadMessage", "Reliable", "Ack",
+    "Traced", "Signed"];
+
+/// What a component adds to the shared [`Component`]
+///
+/// ```ignore
+/// impl RoutingTargets for ComponentALogic {
+///     fn routing_targets() -> &'static [ContainerId] { &[ContainerId::ComponentB] }
+/// }
+///
+/// impl ComponentLogic for ComponentALogic {
+///     fn register_handlers(router: MessageRouter<HandlerContext>, _requester: Requester) -> MessageRouter<HandlerContext> {
+///         router.on::<ExampleMessage, _>(ExampleMessageHandler)
+///     }
+/// }
+///
+/// pub type Component = hyperion_examples_runtime::component::Component<ComponentALogic>;
+/// ```
+pub trait ComponentLogic: RoutingTargets + Send + Sync + 'static {
+    /// Registers the handlers of the component's own messages, next to the [`StandardHandlers`]
+    ///
+    /// # Arguments
+    ///
+    /// * `router` - Router already holding the standard handlers
+    /// * `requester` - Sends requests to other containers and hands their replies back
+    fn register_handlers(router: MessageRouter<HandlerContext>, requester: Requester) -> MessageRouter<HandlerContext>;
+}
+
+/// What every handler of a component can see
+pub struct HandlerContext {
+    /// Current configuration of the component
+    pub config: StdArc<Config>,
+    /// Subscribers of the topics the component publishes to
+    pub topics: TopicRegistry
+}
+
+/// Builds the router dispatching a component's messages to the standard handlers and its own
+pub fn message_router<L: ComponentLogic>(container_name: &str, requester: Requester, topics: &TopicRegistry)
+    -> MessageRouter<HandlerContext> {
+    L::register_handlers(MessageRouter::new().module(StandardHandlers::new(container_name, topics)), requester)
+}
+
+/// Represents a component within the Hyperion Framework.
+/// 
+/// The component is the top level entry point into your specific implementation. Think of it as the
+/// point where Hyperion hands all the specific logic processing over to you, through `L`'s [`ComponentLogic`].
+#[derive(Clone, Debug)]
+pub struct Component<L> {
+    /// Current state of the top level HyperionContainer (which wraps this component)
+    /// This can be set to shutdown through various methods and will auto-kill the component
+    container_state: StdArc<AtomicUsize>,
+    /// Notification mechanism for container state changes
+    container_state_notify: StdArc<Notify>,
+    /// Current state of this component (Active, Dormant, Dead)
+    component_state: ComponentState,
+    /// Specific configuration for this component
+    config: StdArc<Config>,
+    /// Drops, holds or rejects business messages while the component is Dormant
+    dormant: DormantGate,
+    /// Processed message count and recent messages, kept in snapshots
+    history: MessageHistory,
+    /// Checkpoints of the persistent state, None when persistence is disabled
+    state_log: Option<StateLog>,
+    /// Latest ExampleMessage received, i.e. how far the example's exchange has got
+    last_example_message: Option<ExampleMessage>,
+    /// Recovered ExampleMessage to handle again once it has subscribers, continuing the exchange after a restart
+    resume_from: Option<ExampleMessage>,
+    /// Holds messages for unreachable containers once running, None when outbound queueing is disabled
+    outbound: Option<OutboundQueues>,
+    /// Resends business messages until acknowledged once running, None when reliable delivery is disabled
+    reliable_sender: Option<ReliableSender>,
+    /// Acknowledges reliable messages from other containers and drops repeats
+    reliable_receiver: ReliableReceiver,
+    /// This component's subscriptions and the subscribers of the topics it publishes to
+    topics: TopicRegistry,
+    /// The component's routing targets and handlers
+    logic: PhantomData<L>
+}
+
+/// Component specific state checkpointed after every message
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComponentPersistentState {
+    pub last_example_message: Option<ExampleMessage>,
+    /// Reliable messages processed recently per sender, so their repeats aren't processed again after a restart
+    #[serde(default)]
+    pub processed_reliable: HashMap<String, Vec<u64>>
+}
+
+/// Component specific state kept in snapshots
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComponentSnapshotState {
+    pub dormant: bool,
+    /// Messages held while Dormant that haven't been processed yet
+    #[serde(deserialize_with = "local::deserialize_all")]
+    pub held_messages: Vec<ContainerMessage>
+}
+
+// Hyperion Network Containerisation - Component Initialization
+impl<L: ComponentLogic> Initialisable for Component<L> {
+    type ConfigType = Config;
+    
+    /// Initializes a new component instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `container_state` - Atomic reference to container state
+    /// * `container_state_notify` - Notification mechanism for state changes
+    /// * `config` - Component configuration
+    fn initialise(container_state: StdArc<AtomicUsize>, container_state_notify: StdArc<Notify>, config: StdArc<Self::ConfigType>) -> Self {
+        Component::new(container_state, container_state_notify, config)
+    }
+}
+
+impl<L> Snapshot for Component<L> {
+    type State = ComponentSnapshotState;
+
+    fn snapshot_state(&self) -> Self::State {
+        ComponentSnapshotState {
+            dormant: self.component_state == ComponentState::Dormant,
+            held_messages: self.dormant.held_messages()
+        }
+    }
+
+    fn restore_state(&mut self, state: Self::State) {
+        if state.dormant {
+            self.component_state = ComponentState::Dormant;
+        }
+        self.dormant.restore_held(state.held_messages);
+    }
+}
+
+impl<L> PersistentState for Component<L> {
+    type State = ComponentPersistentState;
+
+    fn persistent_state(&self) -> Self::State {
+        ComponentPersistentState {
+            last_example_message: self.last_example_message.clone(),
+            processed_reliable: self.reliable_receiver.processed_ids()
+        }
+    }
+
+    fn recover_state(&mut self, state: Self::State) {
+        self.last_example_message = state.last_example_message;
+        self.reliable_receiver.restore_processed_ids(state.processed_reliable);
+    }
+}
+
+impl<L: ComponentLogic> RoutingTargets for Component<L> {
+    fn routing_targets() -> &'static [ContainerId] {
+        L::routing_targets()
+    }
+}
+
+#[async_trait]
+impl<L: ComponentLogic> Run for Component<L> {
+    type Message = ContainerMessage;
+    
+    /// Main run loop for the component.
+    /// 
+    /// Handles incoming messages and container state changes until the component
+    /// is either killed or the container is shutting down.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `comp_in_rx` - Receiver for incoming component messages
+    /// * `comp_out_tx` - Sender for outgoing broker messages
+    async fn run(mut self, mut comp_in_rx: Receiver<Self::Message>, comp_out_tx: Sender<ClientBrokerMessage<Self::Message>>) {
+        log::info!("{} has started successfully", self.config.container.name);
+        metrics::watch_channel("component_out", &comp_out_tx);
+        // Everything sent from here on leaves the container, so it is signed on the way out
+        let comp_out_tx = sign_outgoing(comp_out_tx, &self.config.container.name);
+        if self.config.snapshot.restore_on_start {
+            self.restore_snapshot();
+        }
+        self.recover_persistent_state();
+        self.outbound = OutboundQueues::start(&self.config.outbound, &self.config.container.name, comp_out_tx.clone());
+        self.reliable_sender = ReliableSender::start(&self.config.reliable, &self.config.container.name, comp_out_tx.clone(),
+            self.outbound.clone());
+        let mut peer_events = PeerMonitor::start(&self.config.heartbeat, &self.config.container.name, comp_out_tx.clone());
+        let requester = Requester::new(&self.config.container.name, comp_out_tx.clone());
+        let mut router = message_router::<L>(&self.config.container.name, requester, &self.topics);
+        router.report_unhandled(&self.config.container.name, HANDLED_BY_COMPONENT);
+        // Let the containers we talk to check our message protocol version
+        add_to_tx_with_retry(&comp_out_tx, &hello_message(&self.config.container.name, L::routing_targets()),
+            &format!("{} main loop", self.config.container.name), &format!("{} Container", self.config.container.name)).await;
+        // Let the containers we connect to know which topics we subscribe to, and learn theirs
+        if let Some(announcement) = self.topics.announcement() {
+            add_to_tx_with_retry(&comp_out_tx, &announcement, &format!("{} main loop", self.config.container.name),
+                &format!("{} Container", self.config.container.name)).await;
+        }
+
+        loop {
+            // Lets the admin endpoint's /ready follow the component's state
+            report_component_state(&self.component_state);
+            if self.component_state == ComponentState::Dead { break; }
+            
+            tokio::select! {
+                // Handle incoming messages
+                Some(message) = comp_in_rx.recv() => {
+                    metrics::observe_channel("component_in", &comp_in_rx);
+                    log::trace!("{} received message: {:?}", self.config.container.name, message);
+                    self.handle_message(&mut router, &comp_out_tx, message).await;
+                    // Catch up on the messages held while Dormant
+                    if self.component_state == ComponentState::Active {
+                        for held_message in self.dormant.release() {
+                            self.handle_message(&mut router, &comp_out_tx, held_message).await;
+                        }
+                    }
+                    self.resume_exchange(&mut router, &comp_out_tx).await;
+                }
+                // Handle peers going down or coming back, as seen by their heartbeats
+                Some(peer_status) = next_peer_status(&mut peer_events) => {
+                    self.handle_message(&mut router, &comp_out_tx, ContainerMessage::PeerStatus(peer_status)).await;
+                }
+                // Handle container state notifications
+                _ = self.container_state_notify.notified() => {
+                    // Check for container shutdown
+                    if self.container_state.load(Ordering::SeqCst) == ContainerState::ShuttingDown as usize {
+                        self.component_state = ComponentState::Dormant;
+                        break;
+                    }
+                }
+            }
+        }
+        report_component_state(&self.component_state);
+        log::info!("{} task has closed", self.config.container.name);
+    }
+}
+
+impl<L: ComponentLogic> Component<L> {
+    /// Creates a new Component instance
+    fn new(container_state: StdArc<AtomicUsize>, container_state_notify: StdArc<Notify>, config: StdArc<Config>) -> Self {
+        Self {
+            container_state,
+            container_state_notify,
+            component_state: ComponentState::Active,
+            config: config.clone(),
+            dormant: DormantGate::new(config.dormant.clone()),
+            history: MessageHistory::new(config.snapshot.last_messages),
+            state_log: StateLog::new(&config.persistence, &config.container.name),
+            last_example_message: None,
+            resume_from: None,
+            outbound: None,
+            reliable_sender: None,
+            reliable_receiver: ReliableReceiver::new(&config.reliable, &config.container.name),
+            topics: TopicRegistry::new(&config.container.name, &config.subscriptions),
+            logic: PhantomData
+        }
+    }
+
+    /// Handles a message in a span continuing the trace it was sent in, if tracing is enabled
+    async fn handle_message(&mut self, router: &mut MessageRouter<HandlerContext>,
+        comp_out_tx: &Sender<ClientBrokerMessage<ContainerMessage>>, message: ContainerMessage) {
+        let (message, span) = trace::receive(&self.config.container.name, message);
+        self.handle_untraced_message(router, comp_out_tx, message).instrument(span).await;
+    }
+
+    /// Processes a message, checkpoints the resulting state and then sends any response
+    ///
+    /// A reliable message is acknowledged once it has been processed and checkpointed, or straight away
+    /// if it is a repeat of one processed before. While Dormant, reliable business messages that are dropped or
+    /// held aren't acknowledged, so their sender keeps them until they are processed.
+    async fn handle_untraced_message(&mut self, router: &mut MessageRouter<HandlerContext>,
+        comp_out_tx: &Sender<ClientBrokerMessage<ContainerMessage>>, message: ContainerMessage) {
+        metrics::record_message(Direction::In, &message);
+        let (message, delivery) = match message {
+            ContainerMessage::Reliable(reliable) => {
+                if self.reliable_receiver.is_repeat(&reliable) {
+                    let ack = self.reliable_receiver.acknowledgement(&reliable.sender, reliable.message_id);
+                    self.send_result(comp_out_tx, ack).await;
+                    return;
+                }
+                if self.component_state == ComponentState::Dormant && is_business_message(&reliable.payload)
+                    && !self.dormant.answers_held_messages() {
+                    // Held messages come back through here once Active, and are acknowledged then
+                    self.dormant.hold(&self.config.container.name, ContainerMessage::Reliable(reliable));
+                    return;
+                }
+                (*reliable.payload, Some((reliable.sender, reliable.message_id)))
+            }
+            ContainerMessage::Ack(ack) => {
+                if let Some(reliable_sender) = &self.reliable_sender {
+                    reliable_sender.acknowledge(&ack);
+                }
+                return;
+            }
+            message => (message, None)
+        };
+
+        let checkpoint = self.component_state == ComponentState::Active && is_business_message(&message);
+        let label = metrics::message_label(&message).to_string();
+        let started = Instant::now();
+        let result = self.process_incoming_message(router, message).await;
+        metrics::observe_handler(&label, started.elapsed());
+        let ack = delivery.map(|(sender, message_id)| self.reliable_receiver.processed(&sender, message_id));
+        if checkpoint {
+            self.checkpoint();
+        }
+        if let Some(ack) = ack {
+            self.send_result(comp_out_tx, ack).await;
+        }
+        if let Some(result) = result {
+            self.send_result(comp_out_tx, result).await;
+        }
+    }
+
+    /// Sends a response message out through the container
+    ///
+    /// Business messages are sent reliably when enabled, and messages for targets that are down are queued.
+    /// Each message is sent in its own span, carrying its trace context if tracing is enabled.
+    async fn send_result(&self, comp_out_tx: &Sender<ClientBrokerMessage<ContainerMessage>>, result: ClientBrokerMessage<ContainerMessage>) {
+        let broker_messages = match &self.reliable_sender {
+            Some(reliable_sender) if is_business_message(&result.message) => reliable_sender.wrap(result),
+            _ => vec![result]
+        };
+        for broker_message in broker_messages {
+            metrics::record_message(Direction::Out, &broker_message.message);
+            let (broker_message, span) = trace::send(&self.config.container.name, broker_message);
+            if let Some(reliable_sender) = &self.reliable_sender {
+                // Kept as sent, so retransmits carry the same trace context
+                reliable_sender.track(&broker_message);
+            }
+            match &self.outbound {
+                Some(outbound) => outbound.send(broker_message).instrument(span).await,
+                None => {
+                    let from_location = format!("{} main loop", self.config.container.name);
+                    let to_location = format!("{} Container", self.config.container.name);
+                    add_to_tx_with_retry(comp_out_tx, &broker_message, &from_location, &to_location).instrument(span).await;
+                }
+            }
+        }
+    }
+
+    /// Processes incoming messages and returns an optional response message
+    /// 
+    /// Component directives are handled here, everything else is dispatched to the handlers registered
+    /// in the router. While Dormant, business messages are handed to the DormantGate instead.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `router` - Dispatches messages to their handlers
+    /// * `message` - The incoming container message to process
+    /// 
+    /// # Returns
+    /// 
+    /// * `Option<ClientBrokerMessage<ContainerMessage>>` - Optional response message
+    async fn process_incoming_message(&mut self, router: &mut MessageRouter<HandlerContext>, message: ContainerMessage)
+        -> Option<ClientBrokerMessage<ContainerMessage>> {
+        if is_business_message(&message) {
+            if self.component_state == ComponentState::Dormant {
+                return self.dormant.hold(&self.config.container.name, message);
+            }
+            self.history.record(&message);
+            if let ContainerMessage::ExampleMessage(example_message) = &message {
+                self.last_example_message = Some(example_message.clone());
+                // The exchange has carried on without the recovered message
+                self.resume_from = None;
+            }
+        }
+        match message {
+            ContainerMessage::ComponentDirectiveMsg(comp_directive) => {
+                log::trace!("{} is processing a component directive: {:?}", self.config.container.name, comp_directive);
+                match comp_directive {
+                    ComponentDirective::SetToActive => {
+                        // Messages held while Dormant are processed by the run loop once this returns
+                        self.component_state = ComponentState::Active;
+                        log::info!("{} is Active", self.config.container.name);
+                    }
+                    ComponentDirective::SetToDormant => {
+                        // Business messages are now dropped, held or rejected depending on <dormant> in configuration.xml
+                        self.component_state = ComponentState::Dormant;
+                        log::info!("{} is Dormant", self.config.container.name);
+                    }
+                    ComponentDirective::WriteToFile => {
+                        self.write_snapshot();
+                    }
+                }
+            }
+            ContainerMessage::UnknownMessage(unknown_message) => {
+                log::warn!("{} could not decode a {} message (protocol version {}): {}", self.config.container.name,
+                    unknown_message.kind, unknown_message.protocol_version, unknown_message.reason);
+            }
+            ContainerMessage::ConfigReloadMessage(reload_message) => {
+                match Config::from_file(&reload_message.config_path) {
+                    Ok(config) => {
+                        let restart_needed = self.config.changes_needing_restart(&config);
+                        if !restart_needed.is_empty() {
+                            log::warn!("{} keeps its current <{}> settings until it is restarted", self.config.container.name,
+                                restart_needed.join(">, <"));
+                        }
+                        self.dormant.configure(config.dormant.clone());
+                        let announcement = self.topics.resubscribe(&config.subscriptions);
+                        self.config = StdArc::new(config);
+                        log::info!("{} has reloaded its configuration from {}", self.config.container.name,
+                            reload_message.config_path);
+                        return announcement;
+                    }
+                    Err(error) => {
+                        log::error!("{} kept its current configuration: {}", self.config.container.name, error);
+                    }
+                }
+            }
+
+            // Handlers for component specific messages are registered through ComponentLogic::register_handlers
+
+            message => {
+                let context = HandlerContext { config: self.config.clone(), topics: self.topics.clone() };
+                match router.dispatch(message, &context).await {
+                    Ok(response) => return response,
+                    Err(message) => {
+                        if self.component_state == ComponentState::Active  {
+                            log::debug!("{} has received an unmapped message: {:?}", self.config.container.name, message);
+                        }
+                    }
+                }
+            }
+        }
+        // Do nothing
+        None
+    }
+
+    /// Writes the component's state to a timestamped file in the configured snapshot directory
+    fn write_snapshot(&self) {
+        let snapshot = self.history.snapshot(self.config.container_identity(), self.snapshot_state());
+        match write_snapshot(&self.config.snapshot, &self.config.container.name, &snapshot) {
+            Ok(path) => log::info!("{} wrote a snapshot to {}", self.config.container.name, path.display()),
+            Err(error) => log::error!("{} could not write a snapshot: {}", self.config.container.name, error)
+        }
+    }
+
+    /// Resumes from the latest snapshot this container wrote, if there is one
+    fn restore_snapshot(&mut self) {
+        match latest_snapshot::<ComponentSnapshotState>(&self.config.snapshot, &self.config.container.name) {
+            Ok(Some((path, snapshot))) => {
+                let version = snapshot.identity.get("version").map(String::as_str).unwrap_or_default();
+                if version != self.config.container.version {
+                    log::warn!("{} is restoring a snapshot written by version {} of the container", self.config.container.name, version);
+                }
+                self.history.restore(&snapshot);
+                self.restore_state(snapshot.state);
+                log::info!("{} resumed from {}", self.config.container.name, path.display());
+            }
+            Ok(None) => log::debug!("{} has no snapshot to resume from", self.config.container.name),
+            Err(error) => log::error!("{} could not restore its snapshot: {}", self.config.container.name, error)
+        }
+    }
+
+    /// Writes the persistent state to the state log, before the response to the message is sent
+    fn checkpoint(&mut self) {
+        let state = self.persistent_state();
+        let Some(state_log) = self.state_log.as_mut() else { return };
+        if let Err(error) = state_log.checkpoint(&state) {
+            log::error!("{} could not checkpoint its state: {}", self.config.container.name, error);
+        }
+    }
+
+    /// Handles the recovered ExampleMessage again once a container subscribes to its responses
+    ///
+    /// Its response was lost with the crash, or sent before it, so handling it again restarts the exchange
+    /// from where it stopped. Subscribers are only known once their Subscribe arrives, so this waits for one.
+    /// A peer that did get the response before the crash receives it twice.
+    async fn resume_exchange(&mut self, router: &mut MessageRouter<HandlerContext>,
+        comp_out_tx: &Sender<ClientBrokerMessage<ContainerMessage>>) {
+        if self.resume_from.is_none() || self.component_state != ComponentState::Active
+            || self.topics.subscribers(EXAMPLE_TOPIC).is_empty() {
+            return;
+        }
+        let Some(example_message) = self.resume_from.take() else { return };
+        log::info!("{} is resuming the exchange from ExampleMessage value {}", self.config.container.name, example_message.value);
+        self.handle_message(router, comp_out_tx, ContainerMessage::ExampleMessage(example_message)).await;
+    }
+
+    /// Continues from the last state checkpointed before the container stopped or crashed
+    fn recover_persistent_state(&mut self) {
+        let Some(state_log) = self.state_log.as_mut() else { return };
+        match state_log.recover::<ComponentPersistentState>() {
+            Ok(Some(state)) => {
+                log::info!("{} recovered its state from {}, last ExampleMessage value {:?}", self.config.container.name,
+                    state_log.path().display(), state.last_example_message.as_ref().map(|message| message.value));
+                self.resume_from = state.last_example_message.clone();
+                self.recover_state(state);
+            }
+            Ok(None) => log::debug!("{} has no checkpointed state to recover", self.config.container.name),
+            Err(error) => log::error!("{} could not recover its state: {}", self.config.container.name, error)
+        }
+    }
+}
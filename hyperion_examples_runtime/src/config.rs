@@ -0,0 +1,124 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! The configuration.xml of the example components.
+
+// Standard
+use std::collections::HashMap;
+
+// Package
+use hyperion_framework::containerisation::traits::{ContainerIdentidy, LogLevel};
+use serde::Deserialize;
+
+// Local
+use crate::config_loader::load_xml;
+use crate::dormant::DormantConfig;
+use crate::outbound::OutboundConfig;
+use crate::peers::HeartbeatConfig;
+use crate::persistence::PersistenceConfig;
+use crate::reliable::ReliableConfig;
+use crate::snapshot::SnapshotConfig;
+use crate::topics::SubscriptionsConfig;
+use crate::error::RuntimeError;
+
+
+// Top level configuration
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    pub container: Container,
+    pub logging: Logging,
+    pub component: ComponentSettings,
+    /// What the component does with messages while Dormant, dropping them when not configured
+    #[serde(default)]
+    pub dormant: DormantConfig,
+    /// Where WriteToFile snapshots go and whether the component resumes from them
+    #[serde(default)]
+    pub snapshot: SnapshotConfig,
+    /// Whether the component checkpoints its state after every message to recover from crashes
+    #[serde(default)]
+    pub persistence: PersistenceConfig,
+    /// Whether messages for containers that are down are queued until they come back
+    #[serde(default)]
+    pub outbound: OutboundConfig,
+    /// Whether business messages are resent until the receiving container acknowledges them
+    #[serde(default)]
+    pub reliable: ReliableConfig,
+    /// Topics whose messages this component receives
+    #[serde(default)]
+    pub subscriptions: SubscriptionsConfig,
+    /// Whether the component sends heartbeats to its peers and tracks which of them are up
+    #[serde(default)]
+    pub heartbeat: HeartbeatConfig
+}
+
+impl Config {
+    /// Reads the configuration from a configuration.xml file, e.g. when reloading at runtime
+    pub fn from_file(path: &str) -> Result<Config, RuntimeError> {
+        load_xml(path)
+    }
+
+    /// Sections changed by a reloaded configuration that only take effect once the container restarts
+    ///
+    /// A reload applies `<component>`, `<dormant>`, `<snapshot>` and `<subscriptions>`. The sections only read by
+    /// the runtime, e.g. `<admin>` or `<authentication>`, aren't compared as they always need a restart.
+    pub fn changes_needing_restart(&self, reloaded: &Config) -> Vec<&'static str> {
+        [
+            ("container", self.container != reloaded.container),
+            ("logging", self.logging != reloaded.logging),
+            ("persistence", self.persistence != reloaded.persistence),
+            ("outbound", self.outbound != reloaded.outbound),
+            ("reliable", self.reliable != reloaded.reliable),
+            ("heartbeat", self.heartbeat != reloaded.heartbeat)
+        ].into_iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(section, _)| section)
+            .collect()
+    }
+}
+
+// Container configuration
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub struct Container {
+    pub name: String,
+    pub version: String,
+    pub version_title: String,
+    pub software_collection: String
+}
+
+/// Only the level is needed here, the runtime's logger reads the rest of `<logging>` itself
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub struct Logging {
+    pub level: String
+}
+
+// Traits
+impl ContainerIdentidy for Config {
+    fn container_identity(&self) -> HashMap<String, String> {
+        let mut identity = HashMap::new();
+        identity.insert("name".to_string(), self.container.name.clone());
+        identity.insert("version".to_string(), self.container.version.clone());
+        identity.insert("version_title".to_string(), self.container.version_title.clone());
+        identity.insert("software_collection".to_string(), self.container.software_collection.clone());
+        identity
+    }
+}
+
+impl LogLevel for Config {
+    fn log_level(&self) -> &str {
+        &self.logging.level
+    }
+}
+
+// Component configuration
+#[derive(Debug, Deserialize)]
+pub struct ComponentSettings {
+    pub increment: u8
+}
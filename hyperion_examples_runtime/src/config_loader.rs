@@ -0,0 +1,37 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Standard
+use std::fs;
+
+// Package
+use serde::de::DeserializeOwned;
+
+// Local
+use crate::error::RuntimeError;
+
+
+/// Reads an XML configuration file into the given type
+///
+/// # Arguments
+///
+/// * `path` - Path to the XML file, e.g. a component's configuration.xml
+pub fn load_xml<T: DeserializeOwned>(path: &str) -> Result<T, RuntimeError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|source| RuntimeError::Io { path: path.to_string(), source })?;
+    parse_xml(path, &contents)
+}
+
+/// Parses XML text into the given type, naming `path` in any error
+pub fn parse_xml<T: DeserializeOwned>(path: &str, contents: &str) -> Result<T, RuntimeError> {
+    quick_xml::de::from_str(contents)
+        .map_err(|error| RuntimeError::Parse { path: path.to_string(), message: error.to_string() })
+}
@@ -0,0 +1,393 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Service discovery over UDP multicast.
+//!
+//! With discovery enabled, a container doesn't rely on the fixed addresses of the `client_connections` in
+//! its network_topology.xml. It multicasts its name, identity and server address on the local network,
+//! and listens for the announcements of other containers. Before the Hyperion container is created, the
+//! runtime waits until every routing target has announced itself and hands Hyperion a topology with the
+//! discovered addresses as client connections.
+//!
+//! Containers keep announcing themselves for as long as they run, so a container that starts later finds
+//! the ones already running, and one that hasn't been heard from for `expire_after_ms` is forgotten. Hyperion's
+//! connections are fixed once the container is created though, so a container only connects to the peers it had
+//! discovered by then.
+
+// Standard
+use std::collections::{BTreeMap, HashMap};
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::sync::{Arc as StdArc, Mutex, MutexGuard};
+use std::time::Instant;
+
+// Package
+use common_messages::routing::container_id::ContainerId;
+use hyperion_framework::containerisation::traits::ContainerIdentidy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use socket2::{Domain, Protocol, Socket, Type};
+
+use tokio::net::UdpSocket;
+use tokio::sync::Notify;
+use tokio::time::{sleep, timeout_at, Duration};
+
+// Local
+use crate::config_loader::load_xml;
+use crate::error::RuntimeError;
+use crate::topology::NetworkTopology;
+
+
+/// Multicast group and port announcements are sent to when the configuration doesn't say
+pub const DEFAULT_DISCOVERY_GROUP: &str = "239.255.80.1:50600";
+/// Delay between announcements when the configuration doesn't say
+pub const DEFAULT_ANNOUNCE_INTERVAL_MS: u64 = 1000;
+/// How long startup waits for the routing targets when the configuration doesn't say
+pub const DEFAULT_DISCOVERY_TIMEOUT_MS: u64 = 30_000;
+/// Silence after which a discovered container is forgotten when the configuration doesn't say
+pub const DEFAULT_EXPIRE_AFTER_MS: u64 = 10_000;
+/// Largest announcement read from the network
+const MAX_ANNOUNCEMENT_SIZE: usize = 4096;
+
+/// The `<discovery>` section of a component's configuration.xml
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscoveryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// IPv4 multicast group and port, e.g. `239.255.80.1:50600`
+    #[serde(default = "default_group")]
+    pub group: String,
+    #[serde(default = "default_announce_interval_ms")]
+    pub announce_interval_ms: u64,
+    /// How long startup waits for every routing target to announce itself
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// How long a discovered container is remembered without announcing itself again
+    #[serde(default = "default_expire_after_ms")]
+    pub expire_after_ms: u64
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            group: default_group(),
+            announce_interval_ms: DEFAULT_ANNOUNCE_INTERVAL_MS,
+            timeout_ms: DEFAULT_DISCOVERY_TIMEOUT_MS,
+            expire_after_ms: DEFAULT_EXPIRE_AFTER_MS
+        }
+    }
+}
+
+fn default_group() -> String {
+    DEFAULT_DISCOVERY_GROUP.to_string()
+}
+
+fn default_announce_interval_ms() -> u64 {
+    DEFAULT_ANNOUNCE_INTERVAL_MS
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_DISCOVERY_TIMEOUT_MS
+}
+
+fn default_expire_after_ms() -> u64 {
+    DEFAULT_EXPIRE_AFTER_MS
+}
+
+/// The parts of configuration.xml read by the runtime rather than the component
+#[derive(Debug, Default, Deserialize)]
+struct DiscoverySettings {
+    #[serde(default)]
+    discovery: DiscoveryConfig
+}
+
+/// What a container multicasts about itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Announcement {
+    pub container_name: String,
+    /// Address of the container's Hyperion server. An unspecified IP (`0.0.0.0`) is replaced by the IP the
+    /// announcement was received from.
+    pub server_address: String,
+    /// The container's `container_identity()`
+    pub identity: BTreeMap<String, String>
+}
+
+impl Announcement {
+    /// Reads an announcement received from the network
+    pub fn parse(packet: &[u8]) -> Result<Announcement, String> {
+        let announcement: Announcement = serde_json::from_slice(packet).map_err(|error| error.to_string())?;
+        if announcement.container_name.is_empty() {
+            return Err("the announcement names no container".to_string());
+        }
+        Ok(announcement)
+    }
+}
+
+/// A container that has announced itself
+#[derive(Debug, Clone)]
+pub struct DiscoveredPeer {
+    pub address: String,
+    pub identity: BTreeMap<String, String>,
+    pub last_seen: Instant
+}
+
+/// Announces this container and records the containers announcing themselves
+#[derive(Debug, Clone)]
+pub struct Discovery {
+    container_name: String,
+    /// Silence after which a discovered container is forgotten
+    expire_after: Duration,
+    peers: StdArc<Mutex<HashMap<String, DiscoveredPeer>>>,
+    /// Notified whenever a new peer is discovered
+    discovered: StdArc<Notify>
+}
+
+impl Discovery {
+    /// Joins the multicast group and starts announcing this container
+    pub fn start(config: &DiscoveryConfig, announcement: Announcement) -> Result<Self, RuntimeError> {
+        let group: SocketAddrV4 = config.group.parse()
+            .map_err(|_| RuntimeError::Discovery(format!("`{}` is not an IPv4 address and port", config.group)))?;
+        if !group.ip().is_multicast() {
+            return Err(RuntimeError::Discovery(format!("{} is not a multicast address", group.ip())));
+        }
+        let socket = StdArc::new(join_group(group)
+            .map_err(|error| RuntimeError::Discovery(format!("unable to join multicast group {}: {}", group, error)))?);
+
+        let discovery = Self::new(&announcement.container_name, Duration::from_millis(config.expire_after_ms));
+        let payload = serde_json::to_vec(&announcement)
+            .map_err(|error| RuntimeError::Discovery(format!("unable to encode the announcement: {}", error)))?;
+        let interval = Duration::from_millis(config.announce_interval_ms.max(1));
+        tokio::spawn(announce(socket.clone(), SocketAddr::V4(group), payload, interval));
+        tokio::spawn(listen(socket, discovery.clone()));
+        log::info!("{} is announcing itself on {}", announcement.container_name, group);
+        Ok(discovery)
+    }
+
+    /// Creates the record of discovered containers, empty until announcements arrive
+    fn new(container_name: &str, expire_after: Duration) -> Self {
+        Self {
+            container_name: container_name.to_string(),
+            expire_after,
+            peers: StdArc::new(Mutex::new(HashMap::new())),
+            discovered: StdArc::new(Notify::new())
+        }
+    }
+
+    /// Waits until every named container has announced itself
+    pub async fn wait_for(&self, names: &[&str], timeout: Duration) -> Result<(), RuntimeError> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // Register for the next discovery before checking, so one arriving in between isn't missed
+            let discovered = self.discovered.notified();
+            let missing: Vec<&str> = {
+                let peers = self.current_peers(Instant::now());
+                names.iter().copied().filter(|name| !peers.contains_key(*name)).collect()
+            };
+            if missing.is_empty() { return Ok(()); }
+            if timeout_at(deadline, discovered).await.is_err() {
+                return Err(RuntimeError::Discovery(format!("{} did not announce themselves within {:?}",
+                    missing.join(", "), timeout)));
+            }
+        }
+    }
+
+    /// Every container discovered so far that is still announcing itself, by name
+    pub fn peers(&self) -> HashMap<String, DiscoveredPeer> {
+        self.current_peers(Instant::now()).clone()
+    }
+
+    /// The discovered containers, after forgetting the ones silent for longer than `expire_after` by `now`
+    fn current_peers(&self, now: Instant) -> MutexGuard<'_, HashMap<String, DiscoveredPeer>> {
+        let mut peers = self.peers.lock().expect("discovery lock poisoned");
+        peers.retain(|name, peer| {
+            let expired = now.saturating_duration_since(peer.last_seen) >= self.expire_after;
+            if expired {
+                log::warn!("{} has not announced itself for {:?} and is forgotten", name, self.expire_after);
+            }
+            !expired
+        });
+        peers
+    }
+
+    /// Records an announcement received from `source`
+    fn record(&self, announcement: Announcement, source: SocketAddr) {
+        if announcement.container_name == self.container_name { return; }
+        let address = match announcement.server_address.parse::<SocketAddr>() {
+            Ok(address) if address.ip().is_unspecified() => SocketAddr::new(source.ip(), address.port()).to_string(),
+            _ => announcement.server_address
+        };
+        let peer = DiscoveredPeer { address: address.clone(), identity: announcement.identity, last_seen: Instant::now() };
+        let previous = self.peers.lock().expect("discovery lock poisoned").insert(announcement.container_name.clone(), peer);
+        match previous {
+            None => {
+                log::info!("Discovered {} at {}", announcement.container_name, address);
+                self.discovered.notify_waiters();
+            }
+            Some(previous) if previous.address != address =>
+                log::warn!("{} moved from {} to {}", announcement.container_name, previous.address, address),
+            Some(_) => {}
+        }
+    }
+}
+
+/// Finds the container's peers when discovery is enabled in its configuration
+///
+/// Waits for every routing target to announce itself, then adds every peer discovered so far to the
+/// topology's client connections.
+///
+/// # Arguments
+///
+/// * `config_path` - The container's rendered configuration.xml
+/// * `topology` - The container's topology, whose public address is announced
+/// * `routing_targets` - Containers that must be discovered before the container can start
+///
+/// # Returns
+///
+/// * `Result<bool, RuntimeError>` - Whether discovery is enabled and the topology was changed
+pub async fn discover_peers<Cfg>(config_path: &str, topology: &mut NetworkTopology, routing_targets: &[ContainerId])
+    -> Result<bool, RuntimeError>
+where
+    Cfg: DeserializeOwned + ContainerIdentidy
+{
+    let settings: DiscoverySettings = load_xml(config_path)?;
+    if !settings.discovery.enabled { return Ok(false); }
+
+    let identity = load_xml::<Cfg>(config_path)?.container_identity().into_iter().collect();
+    let discovery = Discovery::start(&settings.discovery, Announcement {
+        container_name: topology.container_name.clone(),
+        server_address: topology.public_address().to_string(),
+        identity
+    })?;
+    let required: Vec<&str> = routing_targets.iter().map(ContainerId::as_str).collect();
+    log::info!("Waiting for {} to announce themselves", required.join(", "));
+    discovery.wait_for(&required, Duration::from_millis(settings.discovery.timeout_ms)).await?;
+
+    let mut peers: Vec<(String, DiscoveredPeer)> = discovery.peers().into_iter().collect();
+    peers.sort_by(|first, second| first.0.cmp(&second.0));
+    for (name, peer) in peers {
+        topology.set_connection(&name, &peer.address);
+    }
+    Ok(true)
+}
+
+/// Binds a UDP socket to the group's port, shared with other containers on this host, and joins the group
+fn join_group(group: SocketAddrV4) -> std::io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    socket.bind(&SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, group.port())).into())?;
+    socket.join_multicast_v4(group.ip(), &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_multicast_loop_v4(true)?;
+    socket.set_nonblocking(true)?;
+    UdpSocket::from_std(socket.into())
+}
+
+/// Multicasts the announcement for as long as the process runs
+async fn announce(socket: StdArc<UdpSocket>, group: SocketAddr, payload: Vec<u8>, interval: Duration) {
+    loop {
+        if let Err(error) = socket.send_to(&payload, group).await {
+            log::warn!("Unable to send a discovery announcement: {}", error);
+        }
+        sleep(interval).await;
+    }
+}
+
+/// Records the announcements of other containers for as long as the process runs
+async fn listen(socket: StdArc<UdpSocket>, discovery: Discovery) {
+    let mut buffer = vec![0u8; MAX_ANNOUNCEMENT_SIZE];
+    loop {
+        match socket.recv_from(&mut buffer).await {
+            Ok((length, source)) => match Announcement::parse(&buffer[..length]) {
+                Ok(announcement) => discovery.record(announcement, source),
+                Err(error) => log::debug!("Ignored a discovery packet from {}: {}", source, error)
+            },
+            Err(error) => {
+                log::warn!("Unable to receive discovery announcements: {}", error);
+                sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn announcement(container_name: &str, server_address: &str) -> Announcement {
+        Announcement {
+            container_name: container_name.to_string(),
+            server_address: server_address.to_string(),
+            identity: BTreeMap::from([("version".to_string(), "1.0.0".to_string())])
+        }
+    }
+
+    fn source() -> SocketAddr {
+        "192.168.1.20:50600".parse().unwrap()
+    }
+
+    #[test]
+    fn announcements_are_parsed_from_json() {
+        let packet = serde_json::to_vec(&announcement("ComponentB", "127.0.0.1:8001")).unwrap();
+        let parsed = Announcement::parse(&packet).unwrap();
+        assert_eq!(parsed.container_name, "ComponentB");
+        assert_eq!(parsed.server_address, "127.0.0.1:8001");
+        assert_eq!(parsed.identity.get("version").map(String::as_str), Some("1.0.0"));
+
+        assert!(Announcement::parse(b"not json").is_err());
+        assert!(Announcement::parse(br#"{"container_name": "ComponentB"}"#).is_err());
+        let nameless = serde_json::to_vec(&announcement("", "127.0.0.1:8001")).unwrap();
+        assert!(Announcement::parse(&nameless).is_err());
+    }
+
+    #[test]
+    fn an_unspecified_address_takes_the_ip_it_was_sent_from() {
+        let discovery = Discovery::new("ComponentA", Duration::from_secs(10));
+        discovery.record(announcement("ComponentB", "0.0.0.0:8001"), source());
+        discovery.record(announcement("ComponentC", "10.0.0.3:8002"), source());
+        let peers = discovery.peers();
+        assert_eq!(peers["ComponentB"].address, "192.168.1.20:8001");
+        assert_eq!(peers["ComponentC"].address, "10.0.0.3:8002");
+    }
+
+    #[test]
+    fn a_new_announcement_replaces_the_previous_one() {
+        let discovery = Discovery::new("ComponentA", Duration::from_secs(10));
+        discovery.record(announcement("ComponentB", "10.0.0.2:8001"), source());
+        discovery.record(announcement("ComponentB", "10.0.0.9:8001"), source());
+        let peers = discovery.peers();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers["ComponentB"].address, "10.0.0.9:8001");
+        // A container's own announcements aren't recorded
+        discovery.record(announcement("ComponentA", "10.0.0.1:8000"), source());
+        assert!(!discovery.peers().contains_key("ComponentA"));
+    }
+
+    #[test]
+    fn silent_containers_are_forgotten() {
+        let discovery = Discovery::new("ComponentA", Duration::from_secs(10));
+        discovery.record(announcement("ComponentB", "10.0.0.2:8001"), source());
+        discovery.record(announcement("ComponentC", "10.0.0.3:8002"), source());
+        let now = Instant::now();
+        assert_eq!(discovery.current_peers(now + Duration::from_secs(5)).len(), 2);
+        discovery.peers.lock().unwrap().get_mut("ComponentB").unwrap().last_seen = now;
+        discovery.peers.lock().unwrap().get_mut("ComponentC").unwrap().last_seen = now + Duration::from_secs(8);
+        let peers = discovery.current_peers(now + Duration::from_secs(10));
+        assert_eq!(peers.keys().collect::<Vec<_>>(), vec!["ComponentC"]);
+    }
+
+    #[tokio::test]
+    async fn waiting_ends_once_every_container_is_discovered() {
+        let discovery = Discovery::new("ComponentA", Duration::from_secs(10));
+        discovery.record(announcement("ComponentB", "10.0.0.2:8001"), source());
+        assert!(discovery.wait_for(&["ComponentB"], Duration::from_millis(10)).await.is_ok());
+        let error = discovery.wait_for(&["ComponentB", "ComponentC"], Duration::from_millis(10)).await.unwrap_err();
+        assert!(error.to_string().contains("ComponentC did not announce themselves"));
+    }
+}
@@ -0,0 +1,255 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! What a component does with messages while it is Dormant.
+
+// Standard
+use std::collections::VecDeque;
+
+// Package
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::messages::error_message::ErrorMessage;
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+use serde::Deserialize;
+
+
+/// Number of messages held while Dormant when the configuration doesn't say
+pub const DEFAULT_DORMANT_BUFFER_CAPACITY: usize = 100;
+
+/// What happens to business messages while the component is Dormant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum DormantPolicy {
+    /// Discard them
+    #[default]
+    Drop,
+    /// Hold them in a bounded queue and process them once the component is Active again
+    Buffer,
+    /// Discard them, telling the sender of a request with an ErrorMessage reply
+    Reject
+}
+
+/// The `<dormant>` section of a component's configuration.xml
+#[derive(Debug, Clone, Deserialize)]
+pub struct DormantConfig {
+    #[serde(default)]
+    pub policy: DormantPolicy,
+    /// Most messages held by the Buffer policy, the oldest is dropped when full
+    #[serde(default = "default_buffer_capacity")]
+    pub buffer_capacity: usize
+}
+
+impl Default for DormantConfig {
+    fn default() -> Self {
+        Self {
+            policy: DormantPolicy::default(),
+            buffer_capacity: DEFAULT_DORMANT_BUFFER_CAPACITY
+        }
+    }
+}
+
+fn default_buffer_capacity() -> usize {
+    DEFAULT_DORMANT_BUFFER_CAPACITY
+}
+
+/// Whether a message is part of the component's work, rather than keeping the container running
+///
+/// Directives, configuration reloads, the protocol handshake, acknowledgements, subscriptions, heartbeats, peer
+/// status changes and replies to the component's own requests are processed whatever the component's state.
+pub fn is_business_message(message: &ContainerMessage) -> bool {
+    match message {
+        ContainerMessage::ContainerDirectiveMsg(_)
+        | ContainerMessage::ComponentDirectiveMsg(_)
+        | ContainerMessage::ConfigReloadMessage(_)
+        | ContainerMessage::ProtocolHello(_)
+        | ContainerMessage::Ack(_)
+        | ContainerMessage::Subscribe(_)
+        | ContainerMessage::Heartbeat(_)
+        | ContainerMessage::PeerStatus(_) => false,
+        ContainerMessage::Envelope(envelope) => !envelope.is_reply(),
+        ContainerMessage::Reliable(reliable) => is_business_message(&reliable.payload),
+        ContainerMessage::Traced(traced) => is_business_message(&traced.payload),
+        _ => true
+    }
+}
+
+/// Applies the component's DormantPolicy to business messages received while Dormant
+#[derive(Debug, Clone)]
+pub struct DormantGate {
+    config: DormantConfig,
+    held: VecDeque<ContainerMessage>
+}
+
+impl DormantGate {
+    pub fn new(config: DormantConfig) -> Self {
+        Self { config, held: VecDeque::new() }
+    }
+
+    /// Changes the policy, e.g. after a configuration reload. Messages already held are kept.
+    pub fn configure(&mut self, config: DormantConfig) {
+        self.config = config;
+    }
+
+    /// Deals with a business message received while Dormant
+    ///
+    /// # Arguments
+    ///
+    /// * `container_name` - Name of this container, for logs and error replies
+    /// * `message` - The message the component is not processing
+    ///
+    /// # Returns
+    ///
+    /// * `Option<ClientBrokerMessage<ContainerMessage>>` - Error reply to a rejected request
+    pub fn hold(&mut self, container_name: &str, message: ContainerMessage) -> Option<ClientBrokerMessage<ContainerMessage>> {
+        match self.config.policy {
+            DormantPolicy::Drop => {
+                log::debug!("{} is Dormant and dropped a {} message", container_name, message.kind());
+                None
+            }
+            DormantPolicy::Buffer => {
+                if self.config.buffer_capacity == 0 {
+                    log::warn!("{} is Dormant with no buffer capacity and dropped a {} message", container_name, message.kind());
+                    return None;
+                }
+                while self.held.len() >= self.config.buffer_capacity {
+                    if let Some(dropped) = self.held.pop_front() {
+                        log::warn!("{} Dormant buffer is full, dropped the oldest {} message", container_name, dropped.kind());
+                    }
+                }
+                log::debug!("{} is Dormant and holds a {} message ({} held)", container_name, message.kind(), self.held.len() + 1);
+                self.held.push_back(message);
+                None
+            }
+            DormantPolicy::Reject => reject(container_name, message)
+        }
+    }
+
+    /// Whether business messages are answered while Dormant, rather than dropped or held for later
+    pub fn answers_held_messages(&self) -> bool {
+        self.config.policy == DormantPolicy::Reject
+    }
+
+    /// The messages currently held, oldest first, e.g. for a snapshot
+    pub fn held_messages(&self) -> Vec<ContainerMessage> {
+        self.held.iter().cloned().collect()
+    }
+
+    /// Holds messages again that were held before a restart, as long as the buffer has room
+    pub fn restore_held(&mut self, messages: Vec<ContainerMessage>) {
+        let room = self.config.buffer_capacity.saturating_sub(self.held.len());
+        self.held.extend(messages.into_iter().take(room));
+    }
+
+    /// Hands back the held messages, oldest first, to be processed now the component is Active
+    pub fn release(&mut self) -> Vec<ContainerMessage> {
+        self.held.drain(..).collect()
+    }
+}
+
+/// Answers a request with an ErrorMessage. Other messages don't say who sent them, so are only logged.
+fn reject(container_name: &str, message: ContainerMessage) -> Option<ClientBrokerMessage<ContainerMessage>> {
+    let kind = message.kind().to_string();
+    let ContainerMessage::Envelope(envelope) = message else {
+        log::info!("{} is Dormant and rejected a {} message", container_name, kind);
+        return None;
+    };
+    let reply_to = envelope.reply_to.clone()?;
+    log::info!("{} is Dormant and rejected a request from {}", container_name, envelope.sender);
+    let error = ErrorMessage::new(container_name.to_string(), envelope.payload.kind().to_string(),
+        format!("{} is Dormant", container_name));
+    let reply = envelope.reply(container_name.to_string(), ContainerMessage::ErrorMessage(error));
+    Some(ClientBrokerMessage::new(vec![reply_to.as_str()], ContainerMessage::Envelope(reply)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_messages::messages::envelope::Envelope;
+    use common_messages::messages::example_message::ExampleMessage;
+    use common_messages::messages::heartbeat::Heartbeat;
+
+    fn gate(policy: DormantPolicy, buffer_capacity: usize) -> DormantGate {
+        DormantGate::new(DormantConfig { policy, buffer_capacity })
+    }
+
+    fn example(value: u64) -> ContainerMessage {
+        ContainerMessage::ExampleMessage(ExampleMessage::new("work".to_string(), value))
+    }
+
+    fn value_of(message: &ContainerMessage) -> u64 {
+        match message {
+            ContainerMessage::ExampleMessage(example) => example.value,
+            other => panic!("expected an ExampleMessage, got {}", other.kind())
+        }
+    }
+
+    #[test]
+    fn keep_alive_messages_and_replies_are_not_business() {
+        assert!(is_business_message(&example(1)));
+        assert!(!is_business_message(&ContainerMessage::Heartbeat(Heartbeat::new("ComponentA".to_string(), 0))));
+        let request = Envelope::request(1, "ComponentA".to_string(), example(1));
+        let reply = request.reply("ComponentB".to_string(), example(2));
+        assert!(is_business_message(&ContainerMessage::Envelope(request)));
+        assert!(!is_business_message(&ContainerMessage::Envelope(reply)));
+    }
+
+    #[test]
+    fn drop_policy_holds_nothing() {
+        let mut gate = gate(DormantPolicy::Drop, 10);
+        assert!(gate.hold("ComponentB", example(1)).is_none());
+        assert!(gate.release().is_empty());
+        assert!(!gate.answers_held_messages());
+    }
+
+    #[test]
+    fn buffer_policy_releases_the_newest_messages_in_order() {
+        let mut gate = gate(DormantPolicy::Buffer, 2);
+        for value in 1..=3 {
+            assert!(gate.hold("ComponentB", example(value)).is_none());
+        }
+        assert_eq!(gate.held_messages().iter().map(value_of).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(gate.release().iter().map(value_of).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(gate.release().is_empty());
+        assert!(!gate.answers_held_messages());
+    }
+
+    #[test]
+    fn buffer_without_capacity_holds_nothing() {
+        let mut gate = gate(DormantPolicy::Buffer, 0);
+        assert!(gate.hold("ComponentB", example(1)).is_none());
+        assert!(gate.release().is_empty());
+    }
+
+    #[test]
+    fn restored_messages_fill_only_the_free_room() {
+        let mut gate = gate(DormantPolicy::Buffer, 2);
+        gate.hold("ComponentB", example(1));
+        gate.restore_held(vec![example(2), example(3)]);
+        assert_eq!(gate.release().iter().map(value_of).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn reject_policy_answers_requests_with_an_error() {
+        let mut gate = gate(DormantPolicy::Reject, 10);
+        assert!(gate.answers_held_messages());
+        assert!(gate.hold("ComponentB", example(1)).is_none());
+
+        let request = Envelope::request(7, "ComponentA".to_string(), example(1));
+        let reply = gate.hold("ComponentB", ContainerMessage::Envelope(request)).unwrap();
+        assert_eq!(reply.client_name_list, vec!["ComponentA".to_string()]);
+        let ContainerMessage::Envelope(envelope) = reply.message else {
+            panic!("expected an Envelope reply");
+        };
+        assert!(envelope.is_reply());
+        assert_eq!(envelope.correlation_id, 7);
+        assert!(matches!(*envelope.payload, ContainerMessage::ErrorMessage(_)));
+        assert!(gate.release().is_empty());
+    }
+}
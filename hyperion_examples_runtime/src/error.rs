@@ -0,0 +1,70 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Standard
+use std::fmt;
+use std::io;
+
+
+/// Errors raised while preparing a container to run
+#[derive(Debug)]
+pub enum RuntimeError {
+    /// The process was launched with arguments that can't be used
+    InvalidArgument(String),
+    /// A required configuration file does not exist
+    MissingFile { path: String, origin: String },
+    /// A configuration file could not be read
+    Io { path: String, source: io::Error },
+    /// A configuration file was read but its contents are invalid
+    Parse { path: String, message: String },
+    /// A file the runtime produces (e.g. a snapshot) could not be written
+    Write { path: String, source: io::Error },
+    /// Data could not be serialized for writing to a file
+    Serialize { path: String, message: String },
+    /// A component routes messages to a container missing from its network topology
+    UnknownRoutingTarget { target: String, path: String },
+    /// Peers could not be discovered on the network
+    Discovery(String),
+    /// The TLS links between containers could not be set up
+    Tls(String),
+    /// The admin endpoint could not be started
+    Admin(String),
+    /// Spans could not be exported as configured
+    Tracing(String)
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeError::InvalidArgument(message) => write!(f, "invalid argument: {}", message),
+            RuntimeError::MissingFile { path, origin } => write!(f, "{} does not exist (set by {})", path, origin),
+            RuntimeError::Io { path, source } => write!(f, "could not read {}: {}", path, source),
+            RuntimeError::Parse { path, message } => write!(f, "could not parse {}: {}", path, message),
+            RuntimeError::Write { path, source } => write!(f, "could not write {}: {}", path, source),
+            RuntimeError::Serialize { path, message } => write!(f, "could not serialize {}: {}", path, message),
+            RuntimeError::UnknownRoutingTarget { target, path } =>
+                write!(f, "routing target {} is not one of the client_connections in {}", target, path),
+            RuntimeError::Discovery(message) => write!(f, "service discovery failed: {}", message),
+            RuntimeError::Tls(message) => write!(f, "unable to set up TLS: {}", message),
+            RuntimeError::Admin(message) => write!(f, "unable to start the admin endpoint: {}", message),
+            RuntimeError::Tracing(message) => write!(f, "unable to set up tracing: {}", message)
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RuntimeError::Io { source, .. } | RuntimeError::Write { source, .. } => Some(source),
+            _ => None
+        }
+    }
+}
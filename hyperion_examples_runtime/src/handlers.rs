@@ -0,0 +1,189 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! Handlers every component can reuse.
+
+// Package
+use common_messages::messages::error_message::ErrorMessage;
+use common_messages::messages::forward_message::ForwardMessage;
+use common_messages::messages::heartbeat::Heartbeat;
+use common_messages::messages::peer_status::{PeerState, PeerStatus};
+use common_messages::messages::protocol_hello::ProtocolHello;
+use common_messages::messages::subscribe::Subscribe;
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+
+use async_trait::async_trait;
+
+// Local
+use crate::handshake::answer_hello;
+use crate::peers::HeartbeatHandler;
+use crate::router::{HandlerModule, HandlerResult, Handles, MessageRouter};
+use crate::topics::{SubscribeHandler, TopicRegistry};
+
+
+/// Answers the protocol version handshake of other containers
+pub struct HandshakeHandler {
+    container_name: String
+}
+
+impl HandshakeHandler {
+    pub fn new(container_name: &str) -> Self {
+        Self { container_name: container_name.to_string() }
+    }
+}
+
+#[async_trait]
+impl<C: Sync> Handles<ProtocolHello, C> for HandshakeHandler {
+    async fn handle(&mut self, hello: ProtocolHello, _context: &C) -> HandlerResult {
+        answer_hello(&self.container_name, &hello)
+    }
+}
+
+/// Sends messages injected locally (e.g. with the console's `send --to`) on to other containers
+///
+/// ForwardMessages from other containers are rejected when they are received, so only the local console and
+/// runtime can have messages forwarded.
+pub struct ForwardHandler {
+    container_name: String
+}
+
+impl ForwardHandler {
+    pub fn new(container_name: &str) -> Self {
+        Self { container_name: container_name.to_string() }
+    }
+}
+
+#[async_trait]
+impl<C: Sync> Handles<ForwardMessage, C> for ForwardHandler {
+    async fn handle(&mut self, forward_message: ForwardMessage, _context: &C) -> HandlerResult {
+        log::debug!("{} is forwarding a message to {:?}", self.container_name, forward_message.targets);
+        let targets: Vec<&str> = forward_message.targets.iter().map(String::as_str).collect();
+        Some(ClientBrokerMessage::new(targets, *forward_message.message))
+    }
+}
+
+/// Logs the errors other containers report about messages sent to them
+pub struct ErrorMessageHandler {
+    container_name: String
+}
+
+impl ErrorMessageHandler {
+    pub fn new(container_name: &str) -> Self {
+        Self { container_name: container_name.to_string() }
+    }
+}
+
+#[async_trait]
+impl<C: Sync> Handles<ErrorMessage, C> for ErrorMessageHandler {
+    async fn handle(&mut self, error_message: ErrorMessage, _context: &C) -> HandlerResult {
+        log::warn!("{} was told by {} that its {} message was refused: {}", self.container_name, error_message.sender,
+            error_message.kind, error_message.reason);
+        None
+    }
+}
+
+/// Logs the containers this component talks to going down or coming back
+pub struct PeerStatusHandler {
+    container_name: String
+}
+
+impl PeerStatusHandler {
+    pub fn new(container_name: &str) -> Self {
+        Self { container_name: container_name.to_string() }
+    }
+}
+
+#[async_trait]
+impl<C: Sync> Handles<PeerStatus, C> for PeerStatusHandler {
+    async fn handle(&mut self, peer_status: PeerStatus, _context: &C) -> HandlerResult {
+        match (peer_status.previous_state, peer_status.state) {
+            (_, PeerState::Down) => {
+                log::warn!("{} will not hear back from {} until it is up again", self.container_name, peer_status.container);
+            }
+            (PeerState::Down, PeerState::Connected) => {
+                log::info!("{} can exchange messages with {} again", self.container_name, peer_status.container);
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+/// The handlers shared by every component
+pub struct StandardHandlers {
+    container_name: String,
+    topics: TopicRegistry
+}
+
+impl StandardHandlers {
+    /// # Arguments
+    ///
+    /// * `container_name` - This container's name
+    /// * `topics` - Where the subscriptions announced by other containers are recorded
+    pub fn new(container_name: &str, topics: &TopicRegistry) -> Self {
+        Self { container_name: container_name.to_string(), topics: topics.clone() }
+    }
+}
+
+impl<C: Sync + 'static> HandlerModule<C> for StandardHandlers {
+    fn register(self, router: MessageRouter<C>) -> MessageRouter<C> {
+        router
+            .on::<ProtocolHello, _>(HandshakeHandler::new(&self.container_name))
+            .on::<ForwardMessage, _>(ForwardHandler::new(&self.container_name))
+            .on::<ErrorMessage, _>(ErrorMessageHandler::new(&self.container_name))
+            .on::<Subscribe, _>(SubscribeHandler::new(&self.topics))
+            .on::<Heartbeat, _>(HeartbeatHandler::new(&self.container_name))
+            .on::<PeerStatus, _>(PeerStatusHandler::new(&self.container_name))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_messages::messages::container_message::ContainerMessage;
+    use common_messages::messages::example_message::ExampleMessage;
+    use common_messages::messages::peer_status::{PeerState, PeerStatus};
+
+    use crate::topics::SubscriptionsConfig;
+
+    fn standard_router() -> MessageRouter {
+        MessageRouter::new().module(StandardHandlers::new("ComponentA",
+            &TopicRegistry::new("ComponentA", &SubscriptionsConfig::default())))
+    }
+
+    #[test]
+    fn standard_handlers_cover_the_shared_messages() {
+        let router = standard_router();
+        for kind in ["ProtocolHello", "ForwardMessage", "ErrorMessage", "Subscribe", "Heartbeat", "PeerStatus"] {
+            assert!(router.handles(kind), "no handler for {}", kind);
+        }
+        assert!(!router.handles("ExampleMessage"));
+    }
+
+    #[tokio::test]
+    async fn forwarded_messages_are_sent_to_their_targets() {
+        let forward = ForwardMessage::new(vec!["ComponentB".to_string(), "ComponentC".to_string()],
+            ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1)));
+        let result = standard_router().dispatch(ContainerMessage::ForwardMessage(forward), &()).await.unwrap().unwrap();
+        assert_eq!(result.client_name_list, vec!["ComponentB".to_string(), "ComponentC".to_string()]);
+        assert!(matches!(result.message, ContainerMessage::ExampleMessage(_)));
+    }
+
+    #[tokio::test]
+    async fn error_messages_and_peer_status_changes_are_only_logged() {
+        let mut router = standard_router();
+        let error = ErrorMessage::new("ComponentB".to_string(), "ExampleMessage".to_string(), "ComponentB is Dormant".to_string());
+        assert!(router.dispatch(ContainerMessage::ErrorMessage(error), &()).await.unwrap().is_none());
+        let down = PeerStatus::new("ComponentB".to_string(), PeerState::Connected, PeerState::Down);
+        assert!(router.dispatch(ContainerMessage::PeerStatus(down), &()).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,125 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! Protocol version handshake between containers.
+//!
+//! Each component greets its routing targets when it starts, and answers the greeting of any container
+//! that starts after it. Both sides then know whether the other build can decode their messages, and an
+//! incompatible peer is reported once rather than as a stream of undecodable messages.
+
+// Package
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::messages::error_message::ErrorMessage;
+use common_messages::messages::protocol_hello::{ProtocolHello, MIN_SUPPORTED_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use common_messages::routing::container_id::ContainerId;
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+
+
+/// Greets the given containers with this build's protocol version
+pub fn hello_message(own_name: &str, targets: &[ContainerId]) -> ClientBrokerMessage<ContainerMessage> {
+    ContainerId::broker_message(targets, ContainerMessage::ProtocolHello(ProtocolHello::new(own_name.to_string())))
+}
+
+/// Checks a peer's greeting and returns the answer to send back, if one is needed
+///
+/// A compatible greeting is answered with this build's version, an incompatible one with an ErrorMessage
+/// naming both versions. Answers and a container's own greeting aren't answered.
+///
+/// # Arguments
+///
+/// * `own_name` - This container's name
+/// * `hello` - The greeting received from the peer
+pub fn answer_hello(own_name: &str, hello: &ProtocolHello) -> Option<ClientBrokerMessage<ContainerMessage>> {
+    if hello.container == own_name {
+        log::debug!("{} ignored its own protocol greeting", own_name);
+        return None;
+    }
+    let compatible = hello.is_compatible();
+    if !compatible {
+        log::error!("{} runs message protocol version {} (supports {} and newer), which is incompatible with {}'s version {}. \
+            Messages between them will not be understood.",
+            hello.container, hello.protocol_version, hello.min_supported_version, own_name, PROTOCOL_VERSION);
+    } else if hello.protocol_version != PROTOCOL_VERSION {
+        log::info!("{} runs message protocol version {}, which is compatible with {}'s version {}",
+            hello.container, hello.protocol_version, own_name, PROTOCOL_VERSION);
+    } else {
+        log::debug!("{} runs the same message protocol version as {}", hello.container, own_name);
+    }
+
+    if hello.is_reply {
+        return None;
+    }
+    let answer = if compatible {
+        ContainerMessage::ProtocolHello(ProtocolHello::reply(own_name.to_string()))
+    } else {
+        ContainerMessage::ErrorMessage(ErrorMessage::new(own_name.to_string(), "ProtocolHello".to_string(),
+            format!("{} runs message protocol version {} (supports {} and newer), which can't exchange messages with version {}",
+                own_name, PROTOCOL_VERSION, MIN_SUPPORTED_PROTOCOL_VERSION, hello.protocol_version)))
+    };
+    Some(ClientBrokerMessage::new(vec![hello.container.as_str()], answer))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(hello: &ProtocolHello) -> (Vec<String>, ContainerMessage) {
+        let answer = answer_hello("ComponentB", hello).expect("the hello is answered");
+        (answer.client_name_list, answer.message)
+    }
+
+    #[test]
+    fn a_compatible_hello_is_answered_with_this_version() {
+        let (targets, message) = answer(&ProtocolHello::new("ComponentA".to_string()));
+        assert_eq!(targets, vec!["ComponentA".to_string()]);
+        match message {
+            ContainerMessage::ProtocolHello(reply) => {
+                assert!(reply.is_reply);
+                assert_eq!(reply.container, "ComponentB");
+                assert_eq!(reply.protocol_version, PROTOCOL_VERSION);
+            }
+            other => panic!("expected a ProtocolHello reply, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn an_incompatible_hello_is_answered_with_an_error() {
+        let hello = ProtocolHello {
+            protocol_version: PROTOCOL_VERSION + 2,
+            min_supported_version: PROTOCOL_VERSION + 1,
+            ..ProtocolHello::new("ComponentA".to_string())
+        };
+        let (targets, message) = answer(&hello);
+        assert_eq!(targets, vec!["ComponentA".to_string()]);
+        match message {
+            ContainerMessage::ErrorMessage(error) => {
+                assert_eq!(error.sender, "ComponentB");
+                assert_eq!(error.kind, "ProtocolHello");
+                assert!(error.reason.contains(&format!("version {}", PROTOCOL_VERSION + 2)), "{}", error.reason);
+            }
+            other => panic!("expected an ErrorMessage, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn replies_and_own_hellos_are_not_answered() {
+        assert!(answer_hello("ComponentB", &ProtocolHello::reply("ComponentA".to_string())).is_none());
+        assert!(answer_hello("ComponentB", &ProtocolHello::new("ComponentB".to_string())).is_none());
+    }
+
+    #[test]
+    fn hellos_go_to_every_target() {
+        let hello = hello_message("ComponentA", &[ContainerId::ComponentB]);
+        assert_eq!(hello.client_name_list, vec!["ComponentB".to_string()]);
+        assert!(matches!(hello.message, ContainerMessage::ProtocolHello(ProtocolHello { is_reply: false, .. })));
+    }
+}
@@ -0,0 +1,49 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! Shared bootstrap for Hyperion example components.
+//!
+//! Every component binary in this workspace starts a HyperionContainer the same way: container
+//! state atomics, a main message channel, the container factory and a stdin control console.
+//! This crate owns that bootstrap so a component's `main.rs` only has to describe what is specific
+//! to it.
+
+pub mod admin;
+pub mod args;
+pub mod authentication;
+pub mod cli;
+pub mod component;
+pub mod config;
+pub mod config_loader;
+pub mod discovery;
+pub mod dormant;
+pub mod error;
+pub mod handlers;
+pub mod handshake;
+pub mod logging;
+pub mod metrics;
+pub mod outbound;
+pub mod peers;
+pub mod persistence;
+pub mod reliable;
+pub mod request;
+pub mod router;
+pub mod routing;
+pub mod runtime;
+pub mod signals;
+pub mod snapshot;
+pub mod template;
+#[cfg(any(test, feature = "test-certs"))]
+pub mod test_certs;
+pub mod tls;
+pub mod topics;
+pub mod topology;
+pub mod trace;
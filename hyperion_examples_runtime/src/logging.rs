@@ -0,0 +1,516 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! The container's logger: text or JSON records, per-target levels and an optional rotating log file.
+//!
+//! Reads the `<logging>` section of a component's configuration.xml and installs the logger before the Hyperion
+//! container is created, so every record of the process goes through it. `<level>` is still handed to Hyperion
+//! through `LogLevel::log_level`, and is the level of every target without one of its own.
+//!
+//! Every record carries the name of the container it was written by, and the trace id of the span it was written in
+//! while tracing is enabled (see [`crate::trace`]), so the logs of many containers can be aggregated and filtered.
+
+// Standard
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Package
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::Deserialize;
+use serde_json::json;
+
+// Local
+use crate::config_loader::load_xml;
+use crate::error::RuntimeError;
+use crate::trace;
+
+
+/// Level of targets without one of their own when the configuration doesn't say
+pub const DEFAULT_LOG_LEVEL: &str = "Info";
+/// Directory the log file is written to when the configuration doesn't say
+pub const DEFAULT_LOG_DIRECTORY: &str = "logs";
+/// Size the log file is rotated at when the configuration doesn't say
+pub const DEFAULT_MAX_SIZE_MB: u64 = 10;
+/// Number of rotated log files kept when the configuration doesn't say
+pub const DEFAULT_KEEP_FILES: usize = 7;
+
+/// How each record is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum LogFormat {
+    /// One human readable line, e.g. `2025-07-01T09:15:02.123Z INFO  [ComponentA] component_a::component: ...`,
+    /// ending in ` trace_id=<id>` while tracing
+    #[default]
+    Text,
+    /// One JSON object per line with `timestamp`, `level`, `container`, `target`, `message` and, while tracing,
+    /// `trace_id` fields
+    Json
+}
+
+/// How often the log file is rotated regardless of its size, on UTC hour or day boundaries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum LogRotation {
+    #[default]
+    Never,
+    Hourly,
+    Daily
+}
+
+impl LogRotation {
+    /// Length of a rotation period in seconds, None if the file is only rotated by size
+    fn period_secs(self) -> Option<u64> {
+        match self {
+            LogRotation::Never => None,
+            LogRotation::Hourly => Some(3_600),
+            LogRotation::Daily => Some(86_400)
+        }
+    }
+}
+
+/// The `<logging>` section of a component's configuration.xml
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoggingConfig {
+    /// Off, Trace, Debug, Info, Warn or Error
+    #[serde(default = "default_level")]
+    pub level: String,
+    #[serde(default)]
+    pub format: LogFormat,
+    /// Levels of parts of the code, e.g. `component_logic=Trace,hyperion_framework=Warn`. A target matches the
+    /// module paths it is a part of, and the longest matching target wins.
+    #[serde(default)]
+    pub targets: String,
+    #[serde(default)]
+    pub file: LogFileConfig
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: default_level(),
+            format: LogFormat::default(),
+            targets: String::new(),
+            file: LogFileConfig::default()
+        }
+    }
+}
+
+fn default_level() -> String {
+    DEFAULT_LOG_LEVEL.to_string()
+}
+
+/// The `<file>` part of the `<logging>` section
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogFileConfig {
+    /// Whether records are also written to `<directory>/<container name>.log`, besides standard error
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_directory")]
+    pub directory: String,
+    /// Size the file is rotated at, 0 for no limit
+    #[serde(default = "default_max_size_mb")]
+    pub max_size_mb: u64,
+    #[serde(default)]
+    pub rotate_every: LogRotation,
+    /// Rotated files kept, from `<container name>.log.1` (the newest) on; older ones are deleted
+    #[serde(default = "default_keep_files")]
+    pub keep_files: usize
+}
+
+impl Default for LogFileConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: default_directory(),
+            max_size_mb: DEFAULT_MAX_SIZE_MB,
+            rotate_every: LogRotation::default(),
+            keep_files: DEFAULT_KEEP_FILES
+        }
+    }
+}
+
+fn default_directory() -> String {
+    DEFAULT_LOG_DIRECTORY.to_string()
+}
+
+fn default_max_size_mb() -> u64 {
+    DEFAULT_MAX_SIZE_MB
+}
+
+fn default_keep_files() -> usize {
+    DEFAULT_KEEP_FILES
+}
+
+/// The parts of configuration.xml read by the runtime rather than the component
+#[derive(Debug, Default, Deserialize)]
+struct LoggingSettings {
+    #[serde(default)]
+    logging: LoggingConfig
+}
+
+/// Installs the container's logger as configured in `<logging>`
+///
+/// The logger can only be installed once per process, so changes to `<logging>` need a restart.
+///
+/// # Arguments
+///
+/// * `config_path` - The component's configuration.xml
+/// * `container_name` - Name written in every record
+///
+/// # Returns
+///
+/// * `Result<bool, RuntimeError>` - Whether the logger was installed, false if the process already had one
+pub fn start_logging(config_path: &str, container_name: &str) -> Result<bool, RuntimeError> {
+    let config = load_xml::<LoggingSettings>(config_path)?.logging;
+    let filter = TargetFilter::parse(&config.level, &config.targets)
+        .map_err(|message| RuntimeError::Parse { path: config_path.to_string(), message })?;
+    let file = if config.file.enabled {
+        Some(Mutex::new(RotatingFile::open(&config.file, container_name)?))
+    } else {
+        None
+    };
+
+    let max_level = filter.max_level();
+    let logger = ContainerLogger { container_name: container_name.to_string(), format: config.format, filter, file };
+    if log::set_boxed_logger(Box::new(logger)).is_err() {
+        log::warn!("A logger was already installed, the logging settings of {} are not applied", config_path);
+        return Ok(false);
+    }
+    log::set_max_level(max_level);
+    Ok(true)
+}
+
+/// Level of each target, by the longest configured module path it is a part of
+#[derive(Debug, Clone)]
+struct TargetFilter {
+    default: LevelFilter,
+    /// Configured targets, longest first
+    targets: Vec<(String, LevelFilter)>
+}
+
+impl TargetFilter {
+    /// Reads the default level and a comma separated list of `target=level` pairs
+    fn parse(level: &str, targets: &str) -> Result<TargetFilter, String> {
+        let default = parse_level(level)?;
+        let mut parsed = targets.split(',')
+            .map(str::trim)
+            .filter(|directive| !directive.is_empty())
+            .map(|directive| match directive.split_once('=') {
+                Some((target, level)) if !target.trim().is_empty() => Ok((target.trim().to_string(), parse_level(level)?)),
+                _ => Err(format!("logging target `{}` is not of the form target=level", directive))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        parsed.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        Ok(TargetFilter { default, targets: parsed })
+    }
+
+    /// Level of a record's target, e.g. `component_a::component_logic::handlers`
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.targets.iter()
+            .find(|(path, _)| is_part_of(path, target))
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    /// Most verbose level of any target, records above it aren't even formatted
+    fn max_level(&self) -> LevelFilter {
+        self.targets.iter().map(|(_, level)| *level).fold(self.default, Ord::max)
+    }
+}
+
+/// Whether a module path, e.g. `component_logic` or `component_a::component_logic`, is whole segments of a target
+fn is_part_of(path: &str, target: &str) -> bool {
+    target == path
+        || target.starts_with(&format!("{}::", path))
+        || target.ends_with(&format!("::{}", path))
+        || target.contains(&format!("::{}::", path))
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(level.trim())
+        .map_err(|_| format!("unknown log level `{}`, expected Off, Trace, Debug, Info, Warn or Error", level.trim()))
+}
+
+/// Writes the records of every target to standard error and the log file
+#[derive(Debug)]
+struct ContainerLogger {
+    container_name: String,
+    format: LogFormat,
+    filter: TargetFilter,
+    file: Option<Mutex<RotatingFile>>
+}
+
+impl ContainerLogger {
+    fn format_record(&self, record: &Record) -> String {
+        let timestamp = rfc3339(SystemTime::now());
+        match self.format {
+            LogFormat::Text => {
+                let line = format!("{} {:<5} [{}] {}: {}", timestamp, record.level(), self.container_name,
+                    record.target(), record.args());
+                match trace::current_trace_id() {
+                    Some(trace_id) => format!("{} trace_id={}", line, trace_id),
+                    None => line
+                }
+            }
+            LogFormat::Json => {
+                let mut line = json!({
+                    "timestamp": timestamp,
+                    "level": record.level().as_str(),
+                    "container": self.container_name,
+                    "target": record.target(),
+                    "message": record.args().to_string()
+                });
+                if let Some(trace_id) = trace::current_trace_id() {
+                    line["trace_id"] = json!(trace_id);
+                }
+                line.to_string()
+            }
+        }
+    }
+}
+
+impl Log for ContainerLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = self.format_record(record);
+        eprintln!("{}", line);
+        let Some(file) = &self.file else { return };
+        let mut file = file.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Err(error) = file.write_line(&line) {
+            eprintln!("Unable to write to {}: {}", file.path.display(), error);
+        }
+    }
+
+    fn flush(&self) {
+        let Some(file) = &self.file else { return };
+        let mut file = file.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _ = file.file.flush();
+    }
+}
+
+/// Log file that is rotated by size and time, keeping a limited number of rotated files
+#[derive(Debug)]
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    /// Bytes written to the current file, including what it held when opened
+    size: u64,
+    /// Rotation period the current file was started in
+    period: Option<u64>,
+    max_size: Option<u64>,
+    rotate_every: LogRotation,
+    keep_files: usize
+}
+
+impl RotatingFile {
+    /// Opens `<directory>/<container name>.log` for appending, creating the directory if needed
+    fn open(config: &LogFileConfig, container_name: &str) -> Result<Self, RuntimeError> {
+        fs::create_dir_all(&config.directory)
+            .map_err(|source| RuntimeError::Write { path: config.directory.clone(), source })?;
+        let path = Path::new(&config.directory).join(format!("{}.log", container_name));
+        let file = open_append(&path).map_err(|source| RuntimeError::Write { path: path.display().to_string(), source })?;
+        let metadata = file.metadata().map_err(|source| RuntimeError::Io { path: path.display().to_string(), source })?;
+        // A file left by an earlier run belongs to the period it was last written in
+        let period = period_of(config.rotate_every, metadata.modified().unwrap_or_else(|_| SystemTime::now()));
+        Ok(Self {
+            path,
+            file,
+            size: metadata.len(),
+            period,
+            max_size: (config.max_size_mb > 0).then(|| config.max_size_mb * 1024 * 1024),
+            rotate_every: config.rotate_every,
+            keep_files: config.keep_files
+        })
+    }
+
+    /// Appends a line, rotating the file first if the line would take it past its size or its period is over
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let length = line.len() as u64 + 1;
+        let period = period_of(self.rotate_every, SystemTime::now());
+        let too_big = self.max_size.is_some_and(|max_size| self.size > 0 && self.size + length > max_size);
+        if too_big || period != self.period {
+            self.rotate()?;
+            self.period = period;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += length;
+        Ok(())
+    }
+
+    /// Shifts `<name>.log.1` to `<name>.log.2` and so on, dropping the oldest, and starts a new file
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let rotated = |index: usize| PathBuf::from(format!("{}.{}", self.path.display(), index));
+        if self.keep_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            remove_if_exists(&rotated(self.keep_files))?;
+            for index in (1..self.keep_files).rev() {
+                if rotated(index).exists() {
+                    fs::rename(rotated(index), rotated(index + 1))?;
+                }
+            }
+            fs::rename(&self.path, rotated(1))?;
+        }
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(())
+    }
+}
+
+/// Index of the rotation period a time falls in, None if the file isn't rotated by time
+fn period_of(rotation: LogRotation, time: SystemTime) -> Option<u64> {
+    let seconds = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    rotation.period_secs().map(|period| seconds / period)
+}
+
+/// A time as an RFC 3339 UTC timestamp with milliseconds, e.g. `2025-07-01T09:15:02.123Z`
+fn rfc3339(time: SystemTime) -> String {
+    let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let (days, second_of_day) = (elapsed.as_secs() / 86_400, elapsed.as_secs() % 86_400);
+    // Civil date from days since the epoch, see https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z", year, month, day, second_of_day / 3_600,
+        second_of_day % 3_600 / 60, second_of_day % 60, elapsed.subsec_millis())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use log::Level;
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    fn at(seconds: u64, millis: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds) + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn timestamps_are_rfc3339_utc() {
+        assert_eq!(rfc3339(at(0, 0)), "1970-01-01T00:00:00.000Z");
+        assert_eq!(rfc3339(at(951_782_400, 1)), "2000-02-29T00:00:00.001Z");
+        assert_eq!(rfc3339(at(1_709_251_199, 999)), "2024-02-29T23:59:59.999Z");
+        assert_eq!(rfc3339(at(1_751_361_302, 123)), "2025-07-01T09:15:02.123Z");
+        assert_eq!(rfc3339(at(4_102_444_800, 0)), "2100-01-01T00:00:00.000Z");
+    }
+
+    #[test]
+    fn the_longest_matching_target_wins() {
+        let filter = TargetFilter::parse("Info", "component_a=Warn, component_logic=Trace,hyperion_framework=Error").unwrap();
+        assert_eq!(filter.level_for("component_a::component_logic::handlers"), LevelFilter::Trace);
+        assert_eq!(filter.level_for("component_a::component"), LevelFilter::Warn);
+        assert_eq!(filter.level_for("hyperion_framework::containerisation"), LevelFilter::Error);
+        // Targets match whole module path segments only
+        assert_eq!(filter.level_for("component_ab::component"), LevelFilter::Info);
+        assert_eq!(filter.level_for("hyperion_examples_runtime::runtime"), LevelFilter::Info);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn invalid_targets_are_rejected() {
+        assert!(TargetFilter::parse("Loud", "").is_err());
+        assert!(TargetFilter::parse("Info", "component_logic").is_err());
+        assert!(TargetFilter::parse("Info", "=Debug").is_err());
+        assert!(TargetFilter::parse("Info", "component_logic=Loud").is_err());
+        assert!(TargetFilter::parse("Info", " , ").is_ok());
+    }
+
+    #[test]
+    fn text_lines_end_in_the_trace_id_while_tracing() {
+        let logger = ContainerLogger {
+            container_name: "ComponentA".to_string(),
+            format: LogFormat::Text,
+            filter: TargetFilter::parse("Info", "").unwrap(),
+            file: None
+        };
+        let line = || logger.format_record(&Record::builder()
+            .args(format_args!("hello"))
+            .level(Level::Info)
+            .target("component_a::component")
+            .build());
+        assert!(line().ends_with(" INFO  [ComponentA] component_a::component: hello"));
+
+        let provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("logging_tests")));
+        let (traced_line, trace_id) = tracing::subscriber::with_default(subscriber, || {
+            let _entered = tracing::info_span!("handle_message").entered();
+            (line(), trace::current_trace_id().unwrap())
+        });
+        assert!(traced_line.ends_with(&format!("component_a::component: hello trace_id={}", trace_id)));
+    }
+
+    #[test]
+    fn rotation_keeps_the_newest_files() {
+        let directory = std::env::temp_dir().join(format!("hyperion_logs_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let config = LogFileConfig {
+            enabled: true,
+            directory: directory.display().to_string(),
+            keep_files: 2,
+            ..LogFileConfig::default()
+        };
+        let mut file = RotatingFile::open(&config, "ComponentA").unwrap();
+        // Two 9 byte lines per file
+        file.max_size = Some(18);
+        for line in 1..=7 {
+            file.write_line(&format!("line {:03}", line)).unwrap();
+        }
+
+        let read = |name: &str| fs::read_to_string(directory.join(name)).ok();
+        let files = (read("ComponentA.log"), read("ComponentA.log.1"), read("ComponentA.log.2"), read("ComponentA.log.3"));
+        let _ = fs::remove_dir_all(&directory);
+        assert_eq!(files.0.as_deref(), Some("line 007\n"));
+        assert_eq!(files.1.as_deref(), Some("line 005\nline 006\n"));
+        assert_eq!(files.2.as_deref(), Some("line 003\nline 004\n"));
+        assert_eq!(files.3, None);
+    }
+
+    #[test]
+    fn time_rotation_starts_a_file_per_period() {
+        assert_eq!(period_of(LogRotation::Never, at(7_200, 0)), None);
+        assert_eq!(period_of(LogRotation::Hourly, at(7_199, 999)), Some(1));
+        assert_eq!(period_of(LogRotation::Hourly, at(7_200, 0)), Some(2));
+        assert_eq!(period_of(LogRotation::Daily, at(86_399, 0)), Some(0));
+        assert_eq!(period_of(LogRotation::Daily, at(86_400, 0)), Some(1));
+    }
+}
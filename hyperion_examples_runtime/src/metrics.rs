@@ -0,0 +1,249 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Prometheus metrics of a container's message flow.
+//!
+//! The component and the runtime record what happens to messages here, and the admin endpoint's
+//! `GET /metrics` renders it in the Prometheus text format:
+//!
+//! * `hyperion_messages_total` - Messages received and sent by the component, by message type and direction
+//! * `hyperion_handler_duration_seconds` - Time the component took to process a message, by message type
+//! * `hyperion_channel_depth` / `hyperion_channel_capacity` - Messages waiting in the main channel and the
+//!   component's in and out channels
+//! * `hyperion_peer_reconnects_total` - How often a peer came back after being down, as seen by heartbeats
+//!
+//! Message types are labelled by [`message_label`], so messages that couldn't be decoded share one label
+//! whatever type their sender named.
+
+// Standard
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+// Package
+use common_messages::messages::container_message::ContainerMessage;
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+
+
+/// Label of every message that couldn't be decoded
+const UNKNOWN_MESSAGE_LABEL: &str = "UnknownMessage";
+/// Upper bounds of the handler latency histogram buckets, in seconds
+const HANDLER_BUCKETS: &[f64] = &[0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// Every metric of this process
+static METRICS: Mutex<MetricsState> = Mutex::new(MetricsState::new());
+
+/// Whether a message was received or sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction {
+    In,
+    Out
+}
+
+impl Direction {
+    fn label(&self) -> &'static str {
+        match self {
+            Direction::In => "in",
+            Direction::Out => "out"
+        }
+    }
+}
+
+/// Reads the depth and capacity of a channel when the metrics are rendered
+type ChannelProbe = Box<dyn Fn() -> (usize, usize) + Send>;
+
+struct MetricsState {
+    messages: BTreeMap<(String, Direction), u64>,
+    handler_durations: BTreeMap<String, Histogram>,
+    /// Channels sampled when the metrics are rendered
+    channel_probes: BTreeMap<String, ChannelProbe>,
+    /// Channels whose depth is reported by their owner
+    channel_depths: BTreeMap<String, (usize, usize)>,
+    peer_reconnects: BTreeMap<String, u64>
+}
+
+impl MetricsState {
+    const fn new() -> Self {
+        Self {
+            messages: BTreeMap::new(),
+            handler_durations: BTreeMap::new(),
+            channel_probes: BTreeMap::new(),
+            channel_depths: BTreeMap::new(),
+            peer_reconnects: BTreeMap::new()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Observations per bucket of [`HANDLER_BUCKETS`], not cumulative
+    bucket_counts: Vec<u64>,
+    sum: f64,
+    count: u64
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        if self.bucket_counts.is_empty() {
+            self.bucket_counts = vec![0; HANDLER_BUCKETS.len()];
+        }
+        if let Some(bucket) = HANDLER_BUCKETS.iter().position(|upper_bound| value <= *upper_bound) {
+            self.bucket_counts[bucket] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+fn metrics() -> std::sync::MutexGuard<'static, MetricsState> {
+    METRICS.lock().expect("metrics lock poisoned")
+}
+
+/// Message type a message is counted under
+///
+/// Undecodable messages are counted under one label rather than the type their sender named, which would
+/// let another container add a label per type name it makes up.
+pub fn message_label(message: &ContainerMessage) -> &str {
+    match message {
+        ContainerMessage::UnknownMessage(_) => UNKNOWN_MESSAGE_LABEL,
+        message => message.kind()
+    }
+}
+
+/// Counts a message received or sent by the component
+pub fn record_message(direction: Direction, message: &ContainerMessage) {
+    *metrics().messages.entry((message_label(message).to_string(), direction)).or_default() += 1;
+}
+
+/// Records how long the component took to process a message
+///
+/// # Arguments
+///
+/// * `label` - The processed message's [`message_label`]
+/// * `duration` - Time taken to process it
+pub fn observe_handler(label: &str, duration: Duration) {
+    metrics().handler_durations.entry(label.to_string()).or_default().observe(duration.as_secs_f64());
+}
+
+/// Counts a peer coming back after it was down
+pub fn record_reconnect(peer: &str) {
+    *metrics().peer_reconnects.entry(peer.to_string()).or_default() += 1;
+}
+
+/// Reports the depth of a channel whenever the metrics are rendered, without keeping the channel open
+pub fn watch_channel<T: Send + 'static>(channel: &str, sender: &mpsc::Sender<T>) {
+    let sender = sender.downgrade();
+    let probe: ChannelProbe = Box::new(move || match sender.upgrade() {
+        Some(sender) => (sender.max_capacity() - sender.capacity(), sender.max_capacity()),
+        None => (0, 0)
+    });
+    metrics().channel_probes.insert(channel.to_string(), probe);
+}
+
+/// Reports the depth of a channel only its receiver can see, e.g. after receiving from it
+pub fn observe_channel<T>(channel: &str, receiver: &mpsc::Receiver<T>) {
+    metrics().channel_depths.insert(channel.to_string(), (receiver.len(), receiver.max_capacity()));
+}
+
+/// Every metric in the Prometheus text exposition format
+pub fn render() -> String {
+    let metrics = metrics();
+    let mut text = String::new();
+
+    header(&mut text, "hyperion_messages_total", "counter",
+        "Container messages received and sent by the component, by message type and direction");
+    for ((kind, direction), count) in &metrics.messages {
+        line(&mut text, "hyperion_messages_total", &[("kind", kind), ("direction", direction.label())], *count as f64);
+    }
+
+    header(&mut text, "hyperion_handler_duration_seconds", "histogram",
+        "Time the component took to process a message, by message type");
+    for (kind, histogram) in &metrics.handler_durations {
+        let mut cumulative = 0;
+        for (upper_bound, bucket_count) in HANDLER_BUCKETS.iter().zip(&histogram.bucket_counts) {
+            cumulative += bucket_count;
+            line(&mut text, "hyperion_handler_duration_seconds_bucket", &[("kind", kind), ("le", &upper_bound.to_string())],
+                cumulative as f64);
+        }
+        line(&mut text, "hyperion_handler_duration_seconds_bucket", &[("kind", kind), ("le", "+Inf")], histogram.count as f64);
+        line(&mut text, "hyperion_handler_duration_seconds_sum", &[("kind", kind)], histogram.sum);
+        line(&mut text, "hyperion_handler_duration_seconds_count", &[("kind", kind)], histogram.count as f64);
+    }
+
+    let mut channels: BTreeMap<&str, (usize, usize)> = metrics.channel_depths.iter()
+        .map(|(channel, depth)| (channel.as_str(), *depth))
+        .collect();
+    channels.extend(metrics.channel_probes.iter().map(|(channel, probe)| (channel.as_str(), probe())));
+    header(&mut text, "hyperion_channel_depth", "gauge", "Messages waiting in a channel");
+    for (channel, (depth, _)) in &channels {
+        line(&mut text, "hyperion_channel_depth", &[("channel", channel)], *depth as f64);
+    }
+    header(&mut text, "hyperion_channel_capacity", "gauge", "Messages a channel holds before senders have to wait");
+    for (channel, (_, capacity)) in &channels {
+        line(&mut text, "hyperion_channel_capacity", &[("channel", channel)], *capacity as f64);
+    }
+
+    header(&mut text, "hyperion_peer_reconnects_total", "counter", "Times a peer came back after being down");
+    for (peer, count) in &metrics.peer_reconnects {
+        line(&mut text, "hyperion_peer_reconnects_total", &[("peer", peer)], *count as f64);
+    }
+    text
+}
+
+fn header(text: &mut String, name: &str, metric_type: &str, help: &str) {
+    // Writing to a String can't fail
+    let _ = writeln!(text, "# HELP {} {}\n# TYPE {} {}", name, help, name, metric_type);
+}
+
+fn line(text: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    let labels: Vec<String> = labels.iter()
+        .map(|(label, value)| format!("{}=\"{}\"", label, escape_label(value)))
+        .collect();
+    let _ = writeln!(text, "{}{{{}}} {}", name, labels.join(","), value);
+}
+
+/// Escapes a label value as the text format requires
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_messages::messages::example_message::ExampleMessage;
+    use common_messages::messages::unknown_message::UnknownMessage;
+
+    fn unknown(kind: &str) -> ContainerMessage {
+        ContainerMessage::UnknownMessage(UnknownMessage {
+            kind: kind.to_string(),
+            protocol_version: 1,
+            payload: serde_json::Value::Null,
+            reason: "unknown message type".to_string()
+        })
+    }
+
+    #[test]
+    fn known_messages_are_labelled_by_type() {
+        assert_eq!(message_label(&ContainerMessage::ExampleMessage(ExampleMessage::default())), "ExampleMessage");
+    }
+
+    #[test]
+    fn undecodable_messages_share_one_label() {
+        assert_eq!(message_label(&unknown("MadeUpMessage1")), UNKNOWN_MESSAGE_LABEL);
+        assert_eq!(message_label(&unknown("ExampleMessage")), UNKNOWN_MESSAGE_LABEL);
+
+        record_message(Direction::In, &unknown("MadeUpMessage2"));
+        let rendered = render();
+        assert!(rendered.contains("kind=\"UnknownMessage\""));
+        assert!(!rendered.contains("MadeUpMessage2"));
+    }
+}
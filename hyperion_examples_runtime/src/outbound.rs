@@ -0,0 +1,529 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Outbound queues holding messages for peer containers that are down.
+//!
+//! Hyperion sends a component's messages to its peers straight away, so a message for a container that
+//! isn't running is lost once `add_to_tx_with_retry` gives up. With outbound queueing enabled, a component
+//! sends through [`OutboundQueues`] instead: messages for an unreachable peer are kept in a queue per
+//! target, spilled to disk so they survive a restart, and delivered in order once the peer is reachable
+//! again.
+//!
+//! A peer is reachable while the [`PeerMonitor`] sees it Connected, so heartbeats should be enabled along with
+//! the queues. Without them, the peer's address in the network topology is probed with a TCP connection, which
+//! only shows that something accepts connections there. Heartbeats, protocol hellos and subscription replies
+//! for an unreachable peer are dropped rather than queued, as they only make sense while it is up.
+
+// Standard
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc as StdArc, Mutex, OnceLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// Package
+use common_messages::messages::container_message::{local, ContainerMessage};
+use common_messages::messages::peer_status::PeerState;
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+use hyperion_framework::utilities::tx_sender::add_to_tx_with_retry;
+use serde::{Deserialize, Serialize};
+
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::Notify;
+use tokio::time::{sleep, timeout, Duration};
+
+// Local
+use crate::peers::PeerMonitor;
+use crate::topology::NetworkTopology;
+
+
+/// Most messages queued for one target when the configuration doesn't say
+pub const DEFAULT_MAX_QUEUE_DEPTH: usize = 10_000;
+/// First delay before retrying an unreachable peer when the configuration doesn't say
+pub const DEFAULT_INITIAL_BACKOFF_MS: u64 = 500;
+/// Longest delay between retries when the configuration doesn't say
+pub const DEFAULT_MAX_BACKOFF_MS: u64 = 30_000;
+/// How long a peer that answered a probe is assumed to still be reachable
+const PROBE_VALIDITY: Duration = Duration::from_secs(1);
+/// How long to wait for a peer to accept a probe connection
+const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Queues registered by the running component, for the console
+static REGISTERED_QUEUES: OnceLock<OutboundQueues> = OnceLock::new();
+
+/// The `<outbound>` section of a component's configuration.xml
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OutboundConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directory the queues are spilled to
+    #[serde(default = "default_directory")]
+    pub directory: String,
+    /// Most messages queued for one target, the oldest is dropped when full
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64
+}
+
+impl Default for OutboundConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: default_directory(),
+            max_depth: DEFAULT_MAX_QUEUE_DEPTH,
+            initial_backoff_ms: DEFAULT_INITIAL_BACKOFF_MS,
+            max_backoff_ms: DEFAULT_MAX_BACKOFF_MS
+        }
+    }
+}
+
+fn default_directory() -> String {
+    "outbound".to_string()
+}
+
+fn default_max_depth() -> usize {
+    DEFAULT_MAX_QUEUE_DEPTH
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    DEFAULT_INITIAL_BACKOFF_MS
+}
+
+fn default_max_backoff_ms() -> u64 {
+    DEFAULT_MAX_BACKOFF_MS
+}
+
+/// A message waiting for its target, as spilled to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QueuedMessage {
+    /// Milliseconds since the Unix epoch when the message was queued
+    queued_at_ms: u64,
+    #[serde(deserialize_with = "local::deserialize")]
+    message: ContainerMessage
+}
+
+/// Messages waiting for one target container
+#[derive(Debug)]
+struct TargetQueue {
+    /// File the queue is spilled to
+    path: PathBuf,
+    messages: VecDeque<QueuedMessage>,
+    /// Set while queued messages are being sent, so newer messages don't overtake them
+    delivering: bool,
+    backoff: Duration,
+    next_attempt: Instant,
+    /// When the target last answered a probe
+    last_reachable: Option<Instant>
+}
+
+impl TargetQueue {
+    fn new(path: PathBuf, backoff: Duration) -> Self {
+        Self { path, messages: VecDeque::new(), delivering: false, backoff, next_attempt: Instant::now(), last_reachable: None }
+    }
+
+    /// Whether messages for the target have to wait behind earlier ones
+    fn is_queueing(&self) -> bool {
+        self.delivering || !self.messages.is_empty()
+    }
+
+    /// Rewrites the spilled file to match the queue
+    fn spill(&self) {
+        let result = if self.messages.is_empty() {
+            fs::remove_file(&self.path).or_else(|error| match error.kind() {
+                std::io::ErrorKind::NotFound => Ok(()),
+                _ => Err(error)
+            })
+        } else {
+            // Queued messages are signed when they are sent, not while they wait
+            let contents: String = self.messages.iter()
+                .filter_map(|queued| serde_json::to_string(queued).ok())
+                .map(|line| line + "\n")
+                .collect();
+            fs::write(&self.path, contents)
+        };
+        if let Err(error) = result {
+            log::error!("Unable to write outbound queue {}: {}", self.path.display(), error);
+        }
+    }
+
+    /// Appends one message to the spilled file
+    fn spill_message(&self, queued: &QueuedMessage) {
+        let result = serde_json::to_string(queued).map_err(std::io::Error::other).and_then(|line| {
+            if let Some(directory) = self.path.parent() {
+                fs::create_dir_all(directory)?;
+            }
+            OpenOptions::new().create(true).append(true).open(&self.path)?.write_all((line + "\n").as_bytes())
+        });
+        if let Err(error) = result {
+            log::error!("Unable to write outbound queue {}: {}", self.path.display(), error);
+        }
+    }
+}
+
+/// Depth and age of one outbound queue, as shown by the `queues` console command
+#[derive(Debug, Clone)]
+pub struct QueueStatus {
+    pub target: String,
+    pub depth: usize,
+    /// How long the oldest message has been waiting
+    pub oldest_age: Option<Duration>,
+    /// Time until the target is next probed
+    pub next_attempt_in: Duration
+}
+
+/// Sends a component's messages, queueing those for unreachable peers until they come back
+#[derive(Debug, Clone)]
+pub struct OutboundQueues {
+    inner: StdArc<OutboundInner>
+}
+
+#[derive(Debug)]
+struct OutboundInner {
+    config: OutboundConfig,
+    container_name: String,
+    comp_out_tx: Sender<ClientBrokerMessage<ContainerMessage>>,
+    queues: Mutex<HashMap<String, TargetQueue>>,
+    /// Wakes the delivery task when a queue gets its first message
+    wake: Notify
+}
+
+impl OutboundQueues {
+    /// Loads the queues spilled by a previous run and starts delivering them, or returns None when disabled
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The component's `<outbound>` configuration
+    /// * `container_name` - Name of this container, naming the spilled files
+    /// * `comp_out_tx` - The component's sender for outgoing broker messages
+    pub fn start(config: &OutboundConfig, container_name: &str, comp_out_tx: Sender<ClientBrokerMessage<ContainerMessage>>)
+        -> Option<Self> {
+        if !config.enabled { return None; }
+        let queues = Self {
+            inner: StdArc::new(OutboundInner {
+                config: config.clone(),
+                container_name: container_name.to_string(),
+                comp_out_tx,
+                queues: Mutex::new(HashMap::new()),
+                wake: Notify::new()
+            })
+        };
+        queues.inner.load_spilled();
+        if REGISTERED_QUEUES.set(queues.clone()).is_err() {
+            log::warn!("Outbound queues were already started in this process, the console shows the first ones");
+        }
+        tokio::spawn(deliver_queued(queues.inner.clone()));
+        Some(queues)
+    }
+
+    /// Queues of the running component, if it has started any
+    pub fn registered() -> Option<&'static OutboundQueues> {
+        REGISTERED_QUEUES.get()
+    }
+
+    /// Sends a message straight on to the targets that are reachable and have nothing queued, queueing it for the rest
+    ///
+    /// Messages that only matter to a running peer, see [`is_transient`], are dropped instead of queued.
+    pub async fn send(&self, broker_message: ClientBrokerMessage<ContainerMessage>) {
+        let mut direct_targets = Vec::new();
+        for target in &broker_message.client_name_list {
+            if !self.inner.is_queueing(target) && self.inner.is_reachable(target).await {
+                direct_targets.push(target.as_str());
+            } else if is_transient(&broker_message.message) {
+                log::debug!("{} dropped a {} message for {}, which is unreachable", self.inner.container_name,
+                    broker_message.message.kind(), target);
+            } else {
+                self.inner.enqueue(target, &broker_message.message);
+            }
+        }
+        if direct_targets.is_empty() { return; }
+        let direct_message = ClientBrokerMessage::new(direct_targets, broker_message.message.clone());
+        add_to_tx_with_retry(&self.inner.comp_out_tx, &direct_message, &format!("{} outbound queues", self.inner.container_name),
+            &format!("{} Container", self.inner.container_name)).await;
+    }
+
+    /// Whether messages for the target are being held back, i.e. wait behind earlier ones
+    pub fn is_queueing(&self, target: &str) -> bool {
+        self.inner.is_queueing(target)
+    }
+
+    /// Depth and age of every queue holding messages
+    pub fn status(&self) -> Vec<QueueStatus> {
+        let now = Instant::now();
+        let now_ms = now_ms();
+        let queues = self.inner.queues.lock().expect("outbound queues lock poisoned");
+        let mut statuses: Vec<QueueStatus> = queues.iter()
+            .filter(|(_, queue)| queue.is_queueing())
+            .map(|(target, queue)| QueueStatus {
+                target: target.clone(),
+                depth: queue.messages.len(),
+                oldest_age: queue.messages.front().map(|queued| Duration::from_millis(now_ms.saturating_sub(queued.queued_at_ms))),
+                next_attempt_in: queue.next_attempt.saturating_duration_since(now)
+            })
+            .collect();
+        statuses.sort_by(|first, second| first.target.cmp(&second.target));
+        statuses
+    }
+}
+
+impl OutboundInner {
+    fn initial_backoff(&self) -> Duration {
+        Duration::from_millis(self.config.initial_backoff_ms)
+    }
+
+    fn queue_path(&self, target: &str) -> PathBuf {
+        Path::new(&self.config.directory).join(format!("{}-to-{}.queue", self.container_name, target))
+    }
+
+    fn is_queueing(&self, target: &str) -> bool {
+        let queues = self.queues.lock().expect("outbound queues lock poisoned");
+        queues.get(target).is_some_and(TargetQueue::is_queueing)
+    }
+
+    /// Whether the target is up, as seen by its heartbeats, or accepted a connection recently when heartbeats are off
+    async fn is_reachable(&self, target: &str) -> bool {
+        if let Some(reachable) = monitored_reachability(target) {
+            return reachable;
+        }
+        {
+            let queues = self.queues.lock().expect("outbound queues lock poisoned");
+            let recently_reachable = queues.get(target)
+                .and_then(|queue| queue.last_reachable)
+                .is_some_and(|last_reachable| last_reachable.elapsed() < PROBE_VALIDITY);
+            if recently_reachable { return true; }
+        }
+        let reachable = probe(target).await;
+        if reachable {
+            let mut queues = self.queues.lock().expect("outbound queues lock poisoned");
+            let initial_backoff = self.initial_backoff();
+            queues.entry(target.to_string())
+                .or_insert_with(|| TargetQueue::new(self.queue_path(target), initial_backoff))
+                .last_reachable = Some(Instant::now());
+        }
+        reachable
+    }
+
+    fn enqueue(&self, target: &str, message: &ContainerMessage) {
+        let initial_backoff = self.initial_backoff();
+        let mut queues = self.queues.lock().expect("outbound queues lock poisoned");
+        let queue = queues.entry(target.to_string())
+            .or_insert_with(|| TargetQueue::new(self.queue_path(target), initial_backoff));
+        let first_message = !queue.is_queueing();
+        if first_message {
+            log::warn!("{} cannot reach {}, queueing messages for it", self.container_name, target);
+            queue.backoff = initial_backoff;
+            queue.next_attempt = Instant::now() + initial_backoff;
+        }
+
+        let queued = QueuedMessage { queued_at_ms: now_ms(), message: message.clone() };
+        if queue.messages.len() >= self.config.max_depth.max(1) {
+            queue.messages.pop_front();
+            log::warn!("{} outbound queue for {} is full, dropped the oldest message", self.container_name, target);
+            queue.messages.push_back(queued);
+            queue.spill();
+        } else {
+            queue.spill_message(&queued);
+            queue.messages.push_back(queued);
+        }
+        drop(queues);
+        if first_message {
+            self.wake.notify_one();
+        }
+    }
+
+    /// Reads the queues a previous run of this container left on disk
+    fn load_spilled(&self) {
+        let Ok(entries) = fs::read_dir(&self.config.directory) else { return };
+        let prefix = format!("{}-to-", self.container_name);
+        let mut queues = self.queues.lock().expect("outbound queues lock poisoned");
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            if path.extension().is_none_or(|extension| extension != "queue") { continue; }
+            let Some(target) = path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.strip_prefix(&prefix))
+            else { continue };
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(error) => {
+                    log::error!("Unable to read outbound queue {}: {}", path.display(), error);
+                    continue;
+                }
+            };
+            let mut queue = TargetQueue::new(path.clone(), self.initial_backoff());
+            queue.messages = contents.lines()
+                .filter_map(|line| serde_json::from_str::<QueuedMessage>(line).ok())
+                .collect();
+            if queue.messages.is_empty() { continue; }
+            log::info!("{} has {} messages queued for {} from a previous run", self.container_name, queue.messages.len(), target);
+            queues.insert(target.to_string(), queue);
+        }
+    }
+
+    /// Targets with queued messages whose next attempt is due, and the time until the next one that isn't
+    fn due_targets(&self) -> (Vec<String>, Option<Duration>) {
+        let now = Instant::now();
+        let queues = self.queues.lock().expect("outbound queues lock poisoned");
+        let mut due = Vec::new();
+        let mut next_wait: Option<Duration> = None;
+        for (target, queue) in queues.iter().filter(|(_, queue)| !queue.messages.is_empty() && !queue.delivering) {
+            if queue.next_attempt <= now {
+                due.push(target.clone());
+            } else {
+                let wait = queue.next_attempt - now;
+                next_wait = Some(next_wait.map_or(wait, |next_wait| next_wait.min(wait)));
+            }
+        }
+        (due, next_wait)
+    }
+
+    /// Doubles the delay before the target is probed again, up to the configured maximum
+    fn back_off(&self, target: &str) {
+        let max_backoff = Duration::from_millis(self.config.max_backoff_ms);
+        let mut queues = self.queues.lock().expect("outbound queues lock poisoned");
+        if let Some(queue) = queues.get_mut(target) {
+            queue.next_attempt = Instant::now() + queue.backoff;
+            log::debug!("{} still cannot reach {}, retrying in {:?}", self.container_name, target, queue.backoff);
+            queue.backoff = (queue.backoff * 2).min(max_backoff);
+        }
+    }
+
+    /// Sends everything queued for a target in order, including messages queued while sending
+    async fn deliver(&self, target: &str) {
+        let mut delivered = 0;
+        loop {
+            let batch: Vec<QueuedMessage> = {
+                let mut queues = self.queues.lock().expect("outbound queues lock poisoned");
+                let Some(queue) = queues.get_mut(target) else { return };
+                if queue.messages.is_empty() {
+                    queue.delivering = false;
+                    queue.backoff = self.initial_backoff();
+                    queue.last_reachable = Some(Instant::now());
+                    queue.spill();
+                    break;
+                }
+                queue.delivering = true;
+                queue.messages.drain(..).collect()
+            };
+            for queued in batch {
+                let broker_message = ClientBrokerMessage::new(vec![target], queued.message);
+                add_to_tx_with_retry(&self.comp_out_tx, &broker_message, &format!("{} outbound queues", self.container_name),
+                    &format!("{} Container", self.container_name)).await;
+                delivered += 1;
+            }
+        }
+        log::info!("{} can reach {} again and delivered {} queued messages", self.container_name, target, delivered);
+    }
+}
+
+/// Probes and delivers the queues for as long as the process runs
+async fn deliver_queued(inner: StdArc<OutboundInner>) {
+    loop {
+        let (due, next_wait) = inner.due_targets();
+        for target in &due {
+            let reachable = match monitored_reachability(target) {
+                Some(reachable) => reachable,
+                None => probe(target).await
+            };
+            if reachable {
+                inner.deliver(target).await;
+            } else {
+                inner.back_off(target);
+            }
+        }
+        if !due.is_empty() { continue; }
+        match next_wait {
+            Some(wait) => {
+                tokio::select! {
+                    _ = sleep(wait) => {}
+                    _ = inner.wake.notified() => {}
+                }
+            }
+            None => inner.wake.notified().await
+        }
+    }
+}
+
+/// Whether the peer monitor sees the target Connected, None when heartbeats are disabled or don't track it
+///
+/// A peer not heard from yet is not reachable, so messages sent before its first heartbeat arrives are queued.
+fn monitored_reachability(target: &str) -> Option<bool> {
+    PeerMonitor::registered()?.state(target).map(|state| state == PeerState::Connected)
+}
+
+/// Whether a message is only of use to a peer that is up when it arrives, and isn't worth queueing
+///
+/// A peer coming back sends its own heartbeats, hello and subscriptions, which are answered then.
+fn is_transient(message: &ContainerMessage) -> bool {
+    match message {
+        ContainerMessage::Traced(traced) => is_transient(&traced.payload),
+        ContainerMessage::Heartbeat(_) | ContainerMessage::ProtocolHello(_) => true,
+        ContainerMessage::Subscribe(subscribe) => subscribe.is_reply,
+        _ => false
+    }
+}
+
+/// Whether the target's address in the network topology accepts a TCP connection
+///
+/// Used when heartbeats are disabled. Targets without a known address are assumed reachable and left to Hyperion.
+async fn probe(target: &str) -> bool {
+    let Some(connection) = NetworkTopology::current().and_then(|topology| topology.connection(target)) else {
+        return true;
+    };
+    matches!(timeout(PROBE_TIMEOUT, TcpStream::connect(&connection.address)).await, Ok(Ok(_)))
+}
+
+/// Describes the running component's outbound queues for the console
+pub fn status_text() -> String {
+    let Some(queues) = OutboundQueues::registered() else {
+        return "Outbound queueing is disabled for this component".to_string();
+    };
+    let statuses = queues.status();
+    if statuses.is_empty() {
+        return "No messages are queued".to_string();
+    }
+    statuses.iter()
+        .map(|status| format!("{}: {} queued, oldest {:.1}s old, next attempt in {:.1}s", status.target, status.depth,
+            status.oldest_age.unwrap_or_default().as_secs_f64(), status.next_attempt_in.as_secs_f64()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_messages::messages::example_message::ExampleMessage;
+    use common_messages::messages::heartbeat::Heartbeat;
+    use common_messages::messages::subscribe::Subscribe;
+    use common_messages::messages::traced::Traced;
+
+    #[test]
+    fn only_messages_for_a_running_peer_are_transient() {
+        let heartbeat = ContainerMessage::Heartbeat(Heartbeat::new("ComponentA".to_string(), 0));
+        let subscribe = |reply: bool| ContainerMessage::Subscribe(match reply {
+            true => Subscribe::reply("ComponentA".to_string(), vec!["examples".to_string()]),
+            false => Subscribe::new("ComponentA".to_string(), vec!["examples".to_string()])
+        });
+        assert!(is_transient(&heartbeat));
+        assert!(is_transient(&ContainerMessage::Traced(Traced::new("traceparent".to_string(), heartbeat))));
+        assert!(is_transient(&subscribe(true)));
+        // An announcement, e.g. after a reload, still has to reach the peer
+        assert!(!is_transient(&subscribe(false)));
+        assert!(!is_transient(&ContainerMessage::ExampleMessage(ExampleMessage::default())));
+    }
+}
@@ -0,0 +1,407 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Heartbeats and liveness tracking of the containers this container connects to.
+//!
+//! The [`PeerMonitor`] sends a [`Heartbeat`] over every client connection of the network topology at a fixed
+//! interval, and every container answers the heartbeats it receives. A peer is Connected while its heartbeats
+//! or answers arrive, Suspect once they have been missing for `suspect_after_ms` and Down after
+//! `down_after_ms`. Every change is logged and handed to the component as a [`PeerStatus`] message, and the
+//! console's `peers` command prints the current table.
+
+// Standard
+use std::collections::BTreeMap;
+use std::sync::{Arc as StdArc, Mutex, OnceLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// Package
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::messages::heartbeat::Heartbeat;
+use common_messages::messages::peer_status::{PeerState, PeerStatus};
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+use hyperion_framework::utilities::tx_sender::add_to_tx_with_retry;
+use serde::Deserialize;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::time::{sleep, Duration};
+
+// Local
+use crate::metrics;
+use crate::router::{HandlerResult, Handles};
+use crate::topology::NetworkTopology;
+
+
+/// Delay between heartbeats when the configuration doesn't say
+pub const DEFAULT_HEARTBEAT_INTERVAL_MS: u64 = 1000;
+/// Silence after which a peer is suspect when the configuration doesn't say
+pub const DEFAULT_SUSPECT_AFTER_MS: u64 = 3000;
+/// Silence after which a peer is down when the configuration doesn't say
+pub const DEFAULT_DOWN_AFTER_MS: u64 = 10_000;
+/// Peer status changes waiting for the component before further ones are dropped
+const PEER_EVENT_CAPACITY: usize = 32;
+
+/// Monitor started by the running component, for the console
+static REGISTERED_MONITOR: OnceLock<PeerMonitor> = OnceLock::new();
+
+/// The `<heartbeat>` section of a component's configuration.xml
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct HeartbeatConfig {
+    /// Whether this component sends heartbeats and tracks its peers. Heartbeats from other containers are
+    /// always answered.
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+    #[serde(default = "default_suspect_after_ms")]
+    pub suspect_after_ms: u64,
+    #[serde(default = "default_down_after_ms")]
+    pub down_after_ms: u64
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_ms: DEFAULT_HEARTBEAT_INTERVAL_MS,
+            suspect_after_ms: DEFAULT_SUSPECT_AFTER_MS,
+            down_after_ms: DEFAULT_DOWN_AFTER_MS
+        }
+    }
+}
+
+fn default_interval_ms() -> u64 {
+    DEFAULT_HEARTBEAT_INTERVAL_MS
+}
+
+fn default_suspect_after_ms() -> u64 {
+    DEFAULT_SUSPECT_AFTER_MS
+}
+
+fn default_down_after_ms() -> u64 {
+    DEFAULT_DOWN_AFTER_MS
+}
+
+/// What is known about one peer
+#[derive(Debug, Clone)]
+pub struct PeerInfo {
+    pub container: String,
+    pub state: PeerState,
+    /// When a heartbeat or an answer to one last arrived from the peer
+    pub last_seen: Option<Instant>,
+    /// Round trip of the latest answered heartbeat
+    pub round_trip: Option<Duration>
+}
+
+/// Sends heartbeats to the peers and tracks their state
+#[derive(Debug, Clone)]
+pub struct PeerMonitor {
+    inner: StdArc<MonitorInner>
+}
+
+#[derive(Debug)]
+struct MonitorInner {
+    config: HeartbeatConfig,
+    container_name: String,
+    comp_out_tx: Sender<ClientBrokerMessage<ContainerMessage>>,
+    started: Instant,
+    peers: Mutex<BTreeMap<String, PeerInfo>>,
+    events: Sender<PeerStatus>
+}
+
+impl PeerMonitor {
+    /// Starts sending heartbeats to every client connection, or returns None when heartbeats are disabled
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The component's `<heartbeat>` configuration
+    /// * `container_name` - Name of this container, which answers are sent to
+    /// * `comp_out_tx` - The component's sender for outgoing broker messages
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Receiver<PeerStatus>>` - Changes of the peers' states, for the component's run loop
+    pub fn start(config: &HeartbeatConfig, container_name: &str, comp_out_tx: Sender<ClientBrokerMessage<ContainerMessage>>)
+        -> Option<Receiver<PeerStatus>> {
+        if !config.enabled { return None; }
+        let peers = NetworkTopology::current()
+            .map(|topology| topology.client_connections.connection.iter().map(|connection| connection.name.clone()).collect())
+            .unwrap_or_default();
+        let (monitor, receiver) = Self::new(config, container_name, comp_out_tx, peers);
+        if REGISTERED_MONITOR.set(monitor.clone()).is_err() {
+            log::warn!("Heartbeats were already started in this process, the console shows the first peers");
+        }
+        tokio::spawn(send_heartbeats(monitor.inner.clone()));
+        Some(receiver)
+    }
+
+    /// Creates a monitor tracking the given peers, all of them Unknown until heard from
+    fn new(config: &HeartbeatConfig, container_name: &str, comp_out_tx: Sender<ClientBrokerMessage<ContainerMessage>>,
+        peers: Vec<String>) -> (Self, Receiver<PeerStatus>) {
+        let peers = peers.into_iter()
+            .map(|container| (container.clone(), PeerInfo {
+                container,
+                state: PeerState::Unknown,
+                last_seen: None,
+                round_trip: None
+            }))
+            .collect();
+        let (events, receiver) = mpsc::channel(PEER_EVENT_CAPACITY);
+        let monitor = Self {
+            inner: StdArc::new(MonitorInner {
+                config: config.clone(),
+                container_name: container_name.to_string(),
+                comp_out_tx,
+                started: Instant::now(),
+                peers: Mutex::new(peers),
+                events
+            })
+        };
+        (monitor, receiver)
+    }
+
+    /// Monitor of the running component, if it has started one
+    pub fn registered() -> Option<&'static PeerMonitor> {
+        REGISTERED_MONITOR.get()
+    }
+
+    /// Records a heartbeat or answer from a peer, which is Connected from now on
+    pub fn record(&self, heartbeat: &Heartbeat) {
+        let mut peers = self.inner.peers.lock().expect("peer monitor lock poisoned");
+        // Only the client connections are tracked
+        let Some(peer) = peers.get_mut(&heartbeat.sender) else { return };
+        peer.last_seen = Some(Instant::now());
+        if heartbeat.is_reply {
+            peer.round_trip = Some(Duration::from_millis(now_ms().saturating_sub(heartbeat.sent_at_ms)));
+        }
+        self.inner.transition(peer, PeerState::Connected);
+    }
+
+    /// Current state of a peer, None when it isn't one of the client connections
+    pub fn state(&self, container: &str) -> Option<PeerState> {
+        self.inner.peers.lock().expect("peer monitor lock poisoned").get(container).map(|peer| peer.state)
+    }
+
+    /// Every tracked peer, ordered by name
+    pub fn peers(&self) -> Vec<PeerInfo> {
+        self.inner.peers.lock().expect("peer monitor lock poisoned").values().cloned().collect()
+    }
+}
+
+impl MonitorInner {
+    /// Moves a peer to a new state, logging the change and telling the component
+    fn transition(&self, peer: &mut PeerInfo, state: PeerState) {
+        if peer.state == state { return; }
+        let previous_state = peer.state;
+        peer.state = state;
+        if previous_state == PeerState::Down && state == PeerState::Connected {
+            metrics::record_reconnect(&peer.container);
+        }
+        match state {
+            PeerState::Connected => log::info!("{} is connected to {}", self.container_name, peer.container),
+            PeerState::Suspect => log::warn!("{} has missed the heartbeats of {} for {}ms, it may be down",
+                self.container_name, peer.container, self.config.suspect_after_ms),
+            PeerState::Down => log::error!("{} considers {} down", self.container_name, peer.container),
+            PeerState::Unknown => {}
+        }
+        let status = PeerStatus::new(peer.container.clone(), previous_state, state);
+        if self.events.try_send(status).is_err() {
+            log::warn!("{} is not keeping up with peer status changes, the change of {} to {} was dropped",
+                self.container_name, peer.container, state);
+        }
+    }
+
+    /// Marks the peers that have gone quiet by `now` as Suspect or Down
+    fn check_silence(&self, now: Instant) {
+        let suspect_after = Duration::from_millis(self.config.suspect_after_ms);
+        let down_after = Duration::from_millis(self.config.down_after_ms);
+        let mut peers = self.peers.lock().expect("peer monitor lock poisoned");
+        for peer in peers.values_mut() {
+            // Peers never heard from are given the whole down period from startup
+            let silence = now.saturating_duration_since(peer.last_seen.unwrap_or(self.started));
+            let state = if silence >= down_after {
+                PeerState::Down
+            } else if silence >= suspect_after && peer.last_seen.is_some() {
+                PeerState::Suspect
+            } else {
+                continue;
+            };
+            self.transition(peer, state);
+        }
+    }
+}
+
+/// Sends heartbeats and checks the peers' silence for as long as the process runs
+async fn send_heartbeats(inner: StdArc<MonitorInner>) {
+    let interval = Duration::from_millis(inner.config.interval_ms.max(1));
+    loop {
+        let targets: Vec<String> = inner.peers.lock().expect("peer monitor lock poisoned").keys().cloned().collect();
+        if !targets.is_empty() {
+            let heartbeat = Heartbeat::new(inner.container_name.clone(), now_ms());
+            let broker_message = ClientBrokerMessage::new(targets.iter().map(String::as_str).collect(),
+                ContainerMessage::Heartbeat(heartbeat));
+            add_to_tx_with_retry(&inner.comp_out_tx, &broker_message, &format!("{} heartbeats", inner.container_name),
+                &format!("{} Container", inner.container_name)).await;
+        }
+        sleep(interval).await;
+        inner.check_silence(Instant::now());
+    }
+}
+
+/// Waits for the next change of a peer's state, never resolving when heartbeats are disabled
+pub async fn next_peer_status(events: &mut Option<Receiver<PeerStatus>>) -> Option<PeerStatus> {
+    match events {
+        Some(events) => events.recv().await,
+        None => std::future::pending().await
+    }
+}
+
+/// Answers the heartbeats of other containers and records their answers to ours
+pub struct HeartbeatHandler {
+    container_name: String
+}
+
+impl HeartbeatHandler {
+    pub fn new(container_name: &str) -> Self {
+        Self { container_name: container_name.to_string() }
+    }
+}
+
+#[async_trait]
+impl<C: Sync> Handles<Heartbeat, C> for HeartbeatHandler {
+    async fn handle(&mut self, heartbeat: Heartbeat, _context: &C) -> HandlerResult {
+        if let Some(monitor) = PeerMonitor::registered() {
+            monitor.record(&heartbeat);
+        }
+        if heartbeat.is_reply {
+            return None;
+        }
+        let reply = heartbeat.reply(self.container_name.clone());
+        Some(ClientBrokerMessage::new(vec![heartbeat.sender.as_str()], ContainerMessage::Heartbeat(reply)))
+    }
+}
+
+/// Describes the running component's peers for the console
+pub fn status_text() -> String {
+    let Some(monitor) = PeerMonitor::registered() else {
+        return "Heartbeats are disabled for this component".to_string();
+    };
+    let peers = monitor.peers();
+    if peers.is_empty() {
+        return "There are no client connections to monitor".to_string();
+    }
+    let mut lines = vec![format!("{:<20} {:<10} {:<12} {}", "Peer", "State", "Last seen", "Round trip")];
+    lines.extend(peers.iter().map(|peer| {
+        let last_seen = peer.last_seen
+            .map(|last_seen| format!("{:.1}s ago", last_seen.elapsed().as_secs_f64()))
+            .unwrap_or_else(|| "never".to_string());
+        let round_trip = peer.round_trip
+            .map(|round_trip| format!("{}ms", round_trip.as_millis()))
+            .unwrap_or_else(|| "-".to_string());
+        format!("{:<20} {:<10} {:<12} {}", peer.container, peer.state.to_string(), last_seen, round_trip)
+    }));
+    lines.join("\n")
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUSPECT_AFTER: Duration = Duration::from_millis(DEFAULT_SUSPECT_AFTER_MS);
+    const DOWN_AFTER: Duration = Duration::from_millis(DEFAULT_DOWN_AFTER_MS);
+
+    fn monitor() -> (PeerMonitor, Receiver<PeerStatus>) {
+        let (comp_out_tx, _comp_out_rx) = mpsc::channel(1);
+        PeerMonitor::new(&HeartbeatConfig { enabled: true, ..HeartbeatConfig::default() }, "ComponentA", comp_out_tx,
+            vec!["ComponentB".to_string()])
+    }
+
+    fn heartbeat_from_b(monitor: &PeerMonitor) -> Instant {
+        monitor.record(&Heartbeat::new("ComponentB".to_string(), now_ms()));
+        Instant::now()
+    }
+
+    /// The (previous, new) states of the changes reported so far
+    fn changes(events: &mut Receiver<PeerStatus>) -> Vec<(PeerState, PeerState)> {
+        std::iter::from_fn(|| events.try_recv().ok())
+            .inspect(|status| assert_eq!(status.container, "ComponentB"))
+            .map(|status| (status.previous_state, status.state))
+            .collect()
+    }
+
+    #[test]
+    fn a_peer_goes_suspect_then_down_once_it_falls_silent() {
+        let (monitor, mut events) = monitor();
+        let heard = heartbeat_from_b(&monitor);
+        monitor.inner.check_silence(heard + SUSPECT_AFTER / 2);
+        assert_eq!(monitor.state("ComponentB"), Some(PeerState::Connected));
+        monitor.inner.check_silence(heard + SUSPECT_AFTER);
+        assert_eq!(monitor.state("ComponentB"), Some(PeerState::Suspect));
+        monitor.inner.check_silence(heard + DOWN_AFTER);
+        assert_eq!(monitor.state("ComponentB"), Some(PeerState::Down));
+        assert_eq!(changes(&mut events), vec![
+            (PeerState::Unknown, PeerState::Connected),
+            (PeerState::Connected, PeerState::Suspect),
+            (PeerState::Suspect, PeerState::Down)
+        ]);
+    }
+
+    #[test]
+    fn a_peer_never_heard_from_is_only_down_after_the_down_period() {
+        let (monitor, mut events) = monitor();
+        monitor.inner.check_silence(Instant::now() + SUSPECT_AFTER);
+        assert_eq!(monitor.state("ComponentB"), Some(PeerState::Unknown));
+        monitor.inner.check_silence(Instant::now() + DOWN_AFTER);
+        assert_eq!(monitor.state("ComponentB"), Some(PeerState::Down));
+        assert_eq!(changes(&mut events), vec![(PeerState::Unknown, PeerState::Down)]);
+    }
+
+    #[test]
+    fn a_peer_recovers_once_heard_from_again() {
+        let (monitor, mut events) = monitor();
+        let heard = heartbeat_from_b(&monitor);
+        monitor.inner.check_silence(heard + SUSPECT_AFTER);
+        heartbeat_from_b(&monitor);
+        assert_eq!(monitor.state("ComponentB"), Some(PeerState::Connected));
+        monitor.inner.check_silence(Instant::now() + DOWN_AFTER);
+        let heard = heartbeat_from_b(&monitor);
+        assert_eq!(monitor.state("ComponentB"), Some(PeerState::Connected));
+        // Heard from within the period, so it stays Connected
+        monitor.inner.check_silence(heard);
+        assert_eq!(changes(&mut events), vec![
+            (PeerState::Unknown, PeerState::Connected),
+            (PeerState::Connected, PeerState::Suspect),
+            (PeerState::Suspect, PeerState::Connected),
+            (PeerState::Connected, PeerState::Down),
+            (PeerState::Down, PeerState::Connected)
+        ]);
+    }
+
+    #[test]
+    fn answers_give_the_round_trip_and_strangers_are_ignored() {
+        let (monitor, mut events) = monitor();
+        let heartbeat = Heartbeat::new("ComponentA".to_string(), now_ms());
+        monitor.record(&heartbeat.reply("ComponentB".to_string()));
+        let peers = monitor.peers();
+        assert_eq!(peers.len(), 1);
+        assert!(peers[0].round_trip.is_some());
+
+        monitor.record(&Heartbeat::new("ComponentC".to_string(), now_ms()));
+        assert_eq!(monitor.state("ComponentC"), None);
+        assert_eq!(changes(&mut events), vec![(PeerState::Unknown, PeerState::Connected)]);
+    }
+}
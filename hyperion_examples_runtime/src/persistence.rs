@@ -0,0 +1,286 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Write-ahead log of a component's state, so a restarted container recovers where it crashed.
+
+// Standard
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// Package
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+// Local
+use crate::error::RuntimeError;
+
+
+/// Number of checkpoints after which the log is rewritten to hold only the latest state
+pub const DEFAULT_COMPACT_AFTER: usize = 1000;
+
+/// The `<persistence>` section of a component's configuration.xml
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PersistenceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directory holding the state log of each container
+    #[serde(default = "default_directory")]
+    pub directory: String,
+    #[serde(default = "default_compact_after")]
+    pub compact_after: usize
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: default_directory(),
+            compact_after: DEFAULT_COMPACT_AFTER
+        }
+    }
+}
+
+fn default_directory() -> String {
+    "state".to_string()
+}
+
+fn default_compact_after() -> usize {
+    DEFAULT_COMPACT_AFTER
+}
+
+/// State a component checkpoints after handling a message and recovers when it restarts
+pub trait PersistentState {
+    type State: Serialize + DeserializeOwned;
+
+    /// Captures the state to checkpoint
+    fn persistent_state(&self) -> Self::State;
+
+    /// Continues from the last checkpointed state
+    fn recover_state(&mut self, state: Self::State);
+}
+
+/// One line of the state log
+#[derive(Serialize, Deserialize)]
+struct LogEntry<S> {
+    sequence: u64,
+    state: S
+}
+
+/// Appends checkpoints to `<directory>/<container name>.wal`, one JSON document per line
+///
+/// Every checkpoint is synced to disk before the component's response is sent. A crash while writing
+/// leaves at most a torn last line, which recovery skips in favour of the last complete checkpoint.
+#[derive(Debug, Clone)]
+pub struct StateLog {
+    path: PathBuf,
+    sequence: u64,
+    entries: usize,
+    compact_after: usize
+}
+
+impl StateLog {
+    /// Creates the log for a container, or None when persistence is disabled. Nothing is read or written yet.
+    pub fn new(config: &PersistenceConfig, container_name: &str) -> Option<Self> {
+        if !config.enabled { return None; }
+        Some(Self {
+            path: Path::new(&config.directory).join(format!("{}.wal", container_name)),
+            sequence: 0,
+            entries: 0,
+            compact_after: config.compact_after.max(1)
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the last complete checkpoint from the log
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<S>, RuntimeError>` - None when nothing has been checkpointed yet
+    pub fn recover<S: DeserializeOwned>(&mut self) -> Result<Option<S>, RuntimeError> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(RuntimeError::Io { path: self.path.display().to_string(), source })
+        };
+
+        let mut latest = None;
+        for (line_number, line) in contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            match serde_json::from_str::<LogEntry<S>>(line) {
+                Ok(entry) => {
+                    self.sequence = entry.sequence;
+                    latest = Some(entry.state);
+                }
+                Err(error) => log::warn!("Skipped line {} of {}: {}", line_number + 1, self.path.display(), error)
+            }
+            self.entries += 1;
+        }
+        // A torn last line would corrupt the next checkpoint appended to it, so start the next one on a clean log
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            self.entries = self.compact_after;
+        }
+        Ok(latest)
+    }
+
+    /// Appends a checkpoint and syncs it to disk, compacting the log once it has grown long enough
+    pub fn checkpoint<S: Serialize>(&mut self, state: &S) -> Result<(), RuntimeError> {
+        self.sequence += 1;
+        let line = self.entry_line(state)?;
+        if self.entries + 1 >= self.compact_after {
+            return self.compact(&line);
+        }
+
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory).map_err(|source| self.write_error(source))?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)
+            .map_err(|source| self.write_error(source))?;
+        file.write_all(line.as_bytes()).and_then(|_| file.sync_data()).map_err(|source| self.write_error(source))?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Replaces the log with a single checkpoint, through a temporary file so the log is never half written
+    fn compact(&mut self, line: &str) -> Result<(), RuntimeError> {
+        let temporary = self.path.with_extension("wal.tmp");
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory).map_err(|source| self.write_error(source))?;
+        }
+        let mut file = File::create(&temporary).map_err(|source| self.write_error(source))?;
+        file.write_all(line.as_bytes()).and_then(|_| file.sync_data()).map_err(|source| self.write_error(source))?;
+        fs::rename(&temporary, &self.path).map_err(|source| self.write_error(source))?;
+        // The rename only survives a crash once the directory entry is on disk too
+        self.sync_directory()?;
+        log::debug!("Compacted {}", self.path.display());
+        self.entries = 1;
+        Ok(())
+    }
+
+    fn entry_line<S: Serialize>(&self, state: &S) -> Result<String, RuntimeError> {
+        let entry = LogEntry { sequence: self.sequence, state };
+        serde_json::to_string(&entry)
+            .map(|json| json + "\n")
+            .map_err(|error| RuntimeError::Serialize { path: self.path.display().to_string(), message: error.to_string() })
+    }
+
+    /// Syncs the directory holding the log, making a rename into it durable
+    #[cfg(unix)]
+    fn sync_directory(&self) -> Result<(), RuntimeError> {
+        let directory = match self.path.parent() {
+            Some(directory) if !directory.as_os_str().is_empty() => directory,
+            _ => Path::new(".")
+        };
+        File::open(directory).and_then(|directory| directory.sync_all()).map_err(|source| self.write_error(source))
+    }
+
+    /// Directories can't be opened for syncing on this platform, where renames are durable once they return
+    #[cfg(not(unix))]
+    fn sync_directory(&self) -> Result<(), RuntimeError> {
+        Ok(())
+    }
+
+    fn write_error(&self, source: std::io::Error) -> RuntimeError {
+        RuntimeError::Write { path: self.path.display().to_string(), source }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A state log in a directory of its own, removed when the test ends
+    struct TestLog {
+        directory: PathBuf,
+        config: PersistenceConfig
+    }
+
+    impl TestLog {
+        fn new(test: &str, compact_after: usize) -> TestLog {
+            let directory = std::env::temp_dir().join(format!("hyperion_wal_{}_{}", test, std::process::id()));
+            let _ = fs::remove_dir_all(&directory);
+            let config = PersistenceConfig { enabled: true, directory: directory.display().to_string(), compact_after };
+            TestLog { directory, config }
+        }
+
+        fn open(&self) -> StateLog {
+            StateLog::new(&self.config, "ComponentA").unwrap()
+        }
+
+        fn lines(&self) -> Vec<String> {
+            fs::read_to_string(self.directory.join("ComponentA.wal")).unwrap().lines().map(String::from).collect()
+        }
+    }
+
+    impl Drop for TestLog {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.directory);
+        }
+    }
+
+    #[test]
+    fn nothing_is_recovered_without_a_log() {
+        let test_log = TestLog::new("empty", 10);
+        assert_eq!(test_log.open().recover::<u32>().unwrap(), None);
+        assert!(StateLog::new(&PersistenceConfig::default(), "ComponentA").is_none());
+    }
+
+    #[test]
+    fn the_last_checkpoint_is_recovered() {
+        let test_log = TestLog::new("replay", 10);
+        let mut log = test_log.open();
+        for state in 1..=3u32 {
+            log.checkpoint(&state).unwrap();
+        }
+
+        let mut restarted = test_log.open();
+        assert_eq!(restarted.recover::<u32>().unwrap(), Some(3));
+        // Sequence numbers continue where the previous run stopped
+        restarted.checkpoint(&4u32).unwrap();
+        assert!(test_log.lines().last().unwrap().starts_with("{\"sequence\":4,"));
+    }
+
+    #[test]
+    fn a_torn_last_line_is_skipped() {
+        let test_log = TestLog::new("torn", 10);
+        let mut log = test_log.open();
+        log.checkpoint(&1u32).unwrap();
+        log.checkpoint(&2u32).unwrap();
+        let path = test_log.directory.join("ComponentA.wal");
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"sequence\":3,\"sta").unwrap();
+
+        let mut restarted = test_log.open();
+        assert_eq!(restarted.recover::<u32>().unwrap(), Some(2));
+        // The next checkpoint starts a clean log rather than extending the torn line
+        restarted.checkpoint(&3u32).unwrap();
+        assert_eq!(test_log.lines().len(), 1);
+        assert_eq!(test_log.open().recover::<u32>().unwrap(), Some(3));
+    }
+
+    #[test]
+    fn the_log_is_compacted_to_the_latest_checkpoint() {
+        let test_log = TestLog::new("compaction", 3);
+        let mut log = test_log.open();
+        log.checkpoint(&1u32).unwrap();
+        log.checkpoint(&2u32).unwrap();
+        assert_eq!(test_log.lines().len(), 2);
+        log.checkpoint(&3u32).unwrap();
+        assert_eq!(test_log.lines().len(), 1);
+        assert!(!test_log.directory.join("ComponentA.wal.tmp").exists());
+
+        log.checkpoint(&4u32).unwrap();
+        assert_eq!(test_log.lines().len(), 2);
+        assert_eq!(test_log.open().recover::<u32>().unwrap(), Some(4));
+    }
+}
@@ -0,0 +1,394 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Opt-in at-least-once delivery between containers.
+//!
+//! The sending component wraps business messages in a [`Reliable`] with a fresh message id and retransmits
+//! them until the receiving component answers with an [`Ack`]. The receiver only acknowledges a message once it
+//! has processed it, so one it dropped or still holds while Dormant keeps being retransmitted. It remembers the
+//! ids it has processed recently, checkpointed with the component's persistent state, and only acknowledges
+//! repeats, so a message retransmitted after a lost acknowledgement is processed once.
+//!
+//! Retransmits are exact copies of the message first sent, including the trace context it was wrapped in. With
+//! outbound queueing enabled they are sent through the [`OutboundQueues`], so they don't overtake messages queued
+//! for the same target, and aren't sent at all while messages for the target are queued.
+
+// Standard
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc as StdArc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// Package
+use common_messages::messages::ack::Ack;
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::messages::reliable::Reliable;
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+use hyperion_framework::utilities::tx_sender::add_to_tx_with_retry;
+use serde::Deserialize;
+
+use tokio::sync::mpsc::Sender;
+use tokio::time::{sleep, Duration};
+
+// Local
+use crate::outbound::OutboundQueues;
+
+
+/// Delay before an unacknowledged message is sent again when the configuration doesn't say
+pub const DEFAULT_RETRANSMIT_MS: u64 = 2000;
+/// Sends of a message before giving up on it when the configuration doesn't say
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 10;
+/// Message ids remembered per sender for deduplication when the configuration doesn't say
+pub const DEFAULT_DEDUP_WINDOW: usize = 1000;
+
+/// The `<reliable>` section of a component's configuration.xml
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReliableConfig {
+    /// Whether this component sends its business messages reliably. Reliable messages from other
+    /// containers are always acknowledged.
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_retransmit_ms")]
+    pub retransmit_ms: u64,
+    /// Sends of a message, including the first, before it is given up on. 0 retries forever.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    #[serde(default = "default_dedup_window")]
+    pub dedup_window: usize
+}
+
+impl Default for ReliableConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retransmit_ms: DEFAULT_RETRANSMIT_MS,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            dedup_window: DEFAULT_DEDUP_WINDOW
+        }
+    }
+}
+
+fn default_retransmit_ms() -> u64 {
+    DEFAULT_RETRANSMIT_MS
+}
+
+fn default_max_attempts() -> u32 {
+    DEFAULT_MAX_ATTEMPTS
+}
+
+fn default_dedup_window() -> usize {
+    DEFAULT_DEDUP_WINDOW
+}
+
+/// A message sent to one target that hasn't been acknowledged yet
+#[derive(Debug)]
+struct PendingMessage {
+    target: String,
+    /// The [`Reliable`] as it was sent, possibly wrapped in its trace context
+    message: ContainerMessage,
+    last_sent: Instant,
+    attempts: u32
+}
+
+/// Wraps outgoing messages in [`Reliable`] and retransmits them until they are acknowledged
+#[derive(Debug, Clone)]
+pub struct ReliableSender {
+    inner: StdArc<SenderInner>
+}
+
+#[derive(Debug)]
+struct SenderInner {
+    config: ReliableConfig,
+    container_name: String,
+    comp_out_tx: Sender<ClientBrokerMessage<ContainerMessage>>,
+    /// The component's outbound queues, which retransmits go through when enabled
+    outbound: Option<OutboundQueues>,
+    next_message_id: AtomicU64,
+    pending: Mutex<HashMap<u64, PendingMessage>>
+}
+
+impl ReliableSender {
+    /// Starts retransmitting unacknowledged messages, or returns None when reliable delivery is disabled
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The component's `<reliable>` configuration
+    /// * `container_name` - Name of this container, which acknowledgements are sent to
+    /// * `comp_out_tx` - The component's sender for outgoing broker messages
+    /// * `outbound` - The component's outbound queues, if enabled
+    pub fn start(config: &ReliableConfig, container_name: &str, comp_out_tx: Sender<ClientBrokerMessage<ContainerMessage>>,
+        outbound: Option<OutboundQueues>) -> Option<Self> {
+        if !config.enabled { return None; }
+        // Ids start from the clock so a restarted container doesn't reuse ids its peers still remember
+        let first_message_id = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|elapsed| u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX))
+            .unwrap_or(1);
+        let sender = Self {
+            inner: StdArc::new(SenderInner {
+                config: config.clone(),
+                container_name: container_name.to_string(),
+                comp_out_tx,
+                outbound,
+                next_message_id: AtomicU64::new(first_message_id),
+                pending: Mutex::new(HashMap::new())
+            })
+        };
+        tokio::spawn(retransmit(sender.inner.clone()));
+        Some(sender)
+    }
+
+    /// Wraps a message in a [`Reliable`] per target, each with its own message id
+    ///
+    /// Each wrapped message is only retransmitted once it is passed to [`ReliableSender::track`] as it is sent.
+    pub fn wrap(&self, broker_message: ClientBrokerMessage<ContainerMessage>) -> Vec<ClientBrokerMessage<ContainerMessage>> {
+        broker_message.client_name_list.iter().map(|target| {
+            let message_id = self.inner.next_message_id.fetch_add(1, Ordering::Relaxed);
+            let reliable = Reliable::new(message_id, self.inner.container_name.clone(), broker_message.message.clone());
+            ClientBrokerMessage::new(vec![target.as_str()], ContainerMessage::Reliable(reliable))
+        }).collect()
+    }
+
+    /// Retransmits a wrapped message until its target acknowledges it
+    ///
+    /// The message is resent exactly as given, so a [`Reliable`] wrapped in a Traced message keeps its trace
+    /// context on every attempt. Messages that aren't Reliable are ignored.
+    pub fn track(&self, broker_message: &ClientBrokerMessage<ContainerMessage>) {
+        let payload = match &broker_message.message {
+            ContainerMessage::Traced(traced) => traced.payload.as_ref(),
+            message => message
+        };
+        let ContainerMessage::Reliable(reliable) = payload else { return };
+        let Some(target) = broker_message.client_name_list.first() else { return };
+        self.inner.pending.lock().expect("reliable sender lock poisoned").insert(reliable.message_id, PendingMessage {
+            target: target.clone(),
+            message: broker_message.message.clone(),
+            last_sent: Instant::now(),
+            attempts: 1
+        });
+    }
+
+    /// Stops retransmitting an acknowledged message, if the acknowledgement comes from the message's target
+    pub fn acknowledge(&self, ack: &Ack) {
+        let mut pending = self.inner.pending.lock().expect("reliable sender lock poisoned");
+        match pending.get(&ack.message_id) {
+            Some(pending_message) if pending_message.target == ack.sender => {
+                pending.remove(&ack.message_id);
+            }
+            Some(pending_message) => log::warn!("{} ignored an acknowledgement of message {} from {}, it was sent to {}",
+                self.inner.container_name, ack.message_id, ack.sender, pending_message.target),
+            None => log::trace!("{} received a repeated acknowledgement of message {} from {}", self.inner.container_name,
+                ack.message_id, ack.sender)
+        }
+    }
+}
+
+/// Sends unacknowledged messages again for as long as the process runs
+async fn retransmit(inner: StdArc<SenderInner>) {
+    let retransmit_after = Duration::from_millis(inner.config.retransmit_ms.max(1));
+    loop {
+        sleep(retransmit_after / 2).await;
+        let due: Vec<ClientBrokerMessage<ContainerMessage>> = {
+            let mut pending = inner.pending.lock().expect("reliable sender lock poisoned");
+            let max_attempts = inner.config.max_attempts;
+            pending.retain(|message_id, pending_message| {
+                let given_up = max_attempts > 0 && pending_message.attempts >= max_attempts
+                    && pending_message.last_sent.elapsed() >= retransmit_after;
+                if given_up {
+                    log::error!("{} gave up on message {} to {} after {} attempts", inner.container_name, message_id,
+                        pending_message.target, pending_message.attempts);
+                }
+                !given_up
+            });
+            pending.iter_mut()
+                .filter(|(_, pending_message)| pending_message.last_sent.elapsed() >= retransmit_after)
+                .filter_map(|(message_id, pending_message)| {
+                    // A message waiting in the target's outbound queue is sent once the target is back
+                    if inner.outbound.as_ref().is_some_and(|outbound| outbound.is_queueing(&pending_message.target)) {
+                        pending_message.last_sent = Instant::now();
+                        return None;
+                    }
+                    pending_message.attempts += 1;
+                    pending_message.last_sent = Instant::now();
+                    log::debug!("{} is resending message {} to {} (attempt {})", inner.container_name,
+                        message_id, pending_message.target, pending_message.attempts);
+                    Some(ClientBrokerMessage::new(vec![pending_message.target.as_str()], pending_message.message.clone()))
+                })
+                .collect()
+        };
+        for broker_message in due {
+            match &inner.outbound {
+                Some(outbound) => outbound.send(broker_message).await,
+                None => add_to_tx_with_retry(&inner.comp_out_tx, &broker_message,
+                    &format!("{} reliable delivery", inner.container_name), &format!("{} Container", inner.container_name)).await
+            }
+        }
+    }
+}
+
+/// Acknowledges processed [`Reliable`] messages and recognises repeats of them
+#[derive(Debug, Clone)]
+pub struct ReliableReceiver {
+    container_name: String,
+    dedup_window: usize,
+    /// Recently processed message ids per sender, and the order they were processed in
+    seen: HashMap<String, (HashSet<u64>, VecDeque<u64>)>
+}
+
+impl ReliableReceiver {
+    pub fn new(config: &ReliableConfig, container_name: &str) -> Self {
+        Self { container_name: container_name.to_string(), dedup_window: config.dedup_window.max(1), seen: HashMap::new() }
+    }
+
+    /// Whether the message has been processed already, in which case only its acknowledgement is sent again
+    pub fn is_repeat(&self, reliable: &Reliable) -> bool {
+        let repeat = self.seen.get(&reliable.sender).is_some_and(|(ids, _)| ids.contains(&reliable.message_id));
+        if repeat {
+            log::debug!("{} dropped a repeat of message {} from {}", self.container_name, reliable.message_id, reliable.sender);
+        }
+        repeat
+    }
+
+    /// The acknowledgement of a message, to send once it has been processed
+    pub fn acknowledgement(&self, sender: &str, message_id: u64) -> ClientBrokerMessage<ContainerMessage> {
+        ClientBrokerMessage::new(vec![sender], ContainerMessage::Ack(Ack::new(message_id, self.container_name.clone())))
+    }
+
+    /// Remembers a message as processed, so its repeats are recognised
+    ///
+    /// # Returns
+    ///
+    /// * `ClientBrokerMessage<ContainerMessage>` - The acknowledgement to send, once the state including this
+    ///   message has been checkpointed
+    pub fn processed(&mut self, sender: &str, message_id: u64) -> ClientBrokerMessage<ContainerMessage> {
+        let (ids, order) = self.seen.entry(sender.to_string()).or_default();
+        if ids.insert(message_id) {
+            order.push_back(message_id);
+            while order.len() > self.dedup_window {
+                let Some(forgotten) = order.pop_front() else { break };
+                ids.remove(&forgotten);
+            }
+        }
+        self.acknowledgement(sender, message_id)
+    }
+
+    /// The processed message ids per sender, oldest first, for the component's persistent state
+    pub fn processed_ids(&self) -> HashMap<String, Vec<u64>> {
+        self.seen.iter().map(|(sender, (_, order))| (sender.clone(), order.iter().copied().collect())).collect()
+    }
+
+    /// Remembers the message ids processed before a restart, see [`ReliableReceiver::processed_ids`]
+    pub fn restore_processed_ids(&mut self, processed_ids: HashMap<String, Vec<u64>>) {
+        for (sender, message_ids) in processed_ids {
+            for message_id in message_ids {
+                self.processed(&sender, message_id);
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use common_messages::messages::example_message::ExampleMessage;
+    use common_messages::messages::traced::Traced;
+
+    fn receiver(dedup_window: usize) -> ReliableReceiver {
+        ReliableReceiver::new(&ReliableConfig { dedup_window, ..ReliableConfig::default() }, "ComponentB")
+    }
+
+    fn reliable(sender: &str, message_id: u64) -> Reliable {
+        Reliable::new(message_id, sender.to_string(),
+            ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), message_id)))
+    }
+
+    #[test]
+    fn a_message_is_only_a_repeat_once_processed() {
+        let mut receiver = receiver(10);
+        let message = reliable("ComponentA", 1);
+        assert!(!receiver.is_repeat(&message));
+        // Not processed yet, e.g. dropped while Dormant, so a retransmit is processed
+        assert!(!receiver.is_repeat(&message));
+
+        let ack = receiver.processed("ComponentA", 1);
+        assert_eq!(ack.client_name_list, vec!["ComponentA".to_string()]);
+        assert!(matches!(ack.message, ContainerMessage::Ack(Ack { message_id: 1, .. })));
+        assert!(receiver.is_repeat(&message));
+        // Ids are remembered per sender
+        assert!(!receiver.is_repeat(&reliable("ComponentC", 1)));
+    }
+
+    #[test]
+    fn the_oldest_ids_leave_the_dedup_window() {
+        let mut receiver = receiver(2);
+        for message_id in 1..=3 {
+            receiver.processed("ComponentA", message_id);
+        }
+        assert!(!receiver.is_repeat(&reliable("ComponentA", 1)));
+        assert!(receiver.is_repeat(&reliable("ComponentA", 2)));
+        assert!(receiver.is_repeat(&reliable("ComponentA", 3)));
+    }
+
+    #[test]
+    fn processed_ids_survive_a_restart() {
+        let mut receiver = receiver(10);
+        receiver.processed("ComponentA", 7);
+        receiver.processed("ComponentA", 8);
+        let persisted = serde_json::to_string(&receiver.processed_ids()).unwrap();
+
+        let mut restarted = self::receiver(10);
+        restarted.restore_processed_ids(serde_json::from_str(&persisted).unwrap());
+        assert!(restarted.is_repeat(&reliable("ComponentA", 7)));
+        assert!(restarted.is_repeat(&reliable("ComponentA", 8)));
+        assert_eq!(restarted.processed_ids()["ComponentA"], vec![7, 8]);
+    }
+
+    #[tokio::test]
+    async fn only_the_target_can_acknowledge_a_message() {
+        let (comp_out_tx, _comp_out_rx) = mpsc::channel(10);
+        let config = ReliableConfig { enabled: true, ..ReliableConfig::default() };
+        let sender = ReliableSender::start(&config, "ComponentA", comp_out_tx, None).unwrap();
+        let wrapped = sender.wrap(ClientBrokerMessage::new(vec!["ComponentB"],
+            ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1))));
+        let ContainerMessage::Reliable(reliable) = &wrapped[0].message else {
+            panic!("expected a Reliable message");
+        };
+        sender.track(&wrapped[0]);
+        let pending = || sender.inner.pending.lock().unwrap().len();
+
+        sender.acknowledge(&Ack::new(reliable.message_id, "ComponentC".to_string()));
+        assert_eq!(pending(), 1);
+        sender.acknowledge(&Ack::new(reliable.message_id, "ComponentB".to_string()));
+        assert_eq!(pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn retransmits_keep_their_trace_context() {
+        let (comp_out_tx, mut comp_out_rx) = mpsc::channel(10);
+        let config = ReliableConfig { enabled: true, retransmit_ms: 20, ..ReliableConfig::default() };
+        let sender = ReliableSender::start(&config, "ComponentA", comp_out_tx, None).unwrap();
+        let wrapped = sender.wrap(ClientBrokerMessage::new(vec!["ComponentB"],
+            ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1))));
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string();
+        let traced = ClientBrokerMessage::new(vec!["ComponentB"],
+            ContainerMessage::Traced(Traced::new(traceparent.clone(), wrapped[0].message.clone())));
+        sender.track(&traced);
+
+        let retransmit = tokio::time::timeout(Duration::from_secs(5), comp_out_rx.recv()).await.unwrap().unwrap();
+        assert_eq!(retransmit.client_name_list, vec!["ComponentB".to_string()]);
+        let ContainerMessage::Traced(retransmitted) = retransmit.message else {
+            panic!("expected the retransmit to keep its trace context");
+        };
+        assert_eq!(retransmitted.traceparent, traceparent);
+        assert!(matches!(*retransmitted.payload, ContainerMessage::Reliable(_)));
+    }
+}
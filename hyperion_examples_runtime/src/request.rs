@@ -0,0 +1,262 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! Request/response interactions between containers.
+//!
+//! Messages between containers are fire-and-forget. A [`Requester`] sends a message inside an
+//! [`Envelope`] and waits for the receiving component to answer it, which lets components be written
+//! in an RPC style:
+//!
+//! ```ignore
+//! let requester = Requester::new(name, comp_out_tx.clone());
+//! let reply = requester.request(ContainerId::ComponentB, message, Duration::from_secs(5)).await?;
+//! ```
+//!
+//! The component owning the requester must hand incoming reply envelopes to [`Requester::resolve`].
+//! As the reply arrives through the component's own run loop, requests should be awaited outside of
+//! it, e.g. in a spawned task. Only a reply from the request's target answers it.
+
+// Standard
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc as StdArc, Mutex};
+
+// Package
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::messages::envelope::Envelope;
+use common_messages::routing::container_id::ContainerId;
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+use hyperion_framework::utilities::tx_sender::add_to_tx_with_retry;
+
+use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
+use tokio::time::{timeout, Duration};
+use tracing::Instrument;
+
+// Local
+use crate::trace;
+
+
+/// Payload of the reply to a request
+pub type Reply = ContainerMessage;
+
+/// Requests awaiting their reply by correlation id, with the container each was sent to
+type PendingRequests = HashMap<u64, (String, oneshot::Sender<Reply>)>;
+
+/// Reasons a request did not get a reply
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestError {
+    /// No reply arrived within the timeout
+    Timeout { target: ContainerId, timeout: Duration },
+    /// The requester was dropped before the reply arrived
+    Abandoned { target: ContainerId }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::Timeout { target, timeout } => write!(f, "{} did not reply within {:?}", target, timeout),
+            RequestError::Abandoned { target } => write!(f, "the request to {} was abandoned", target)
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+/// Sends requests through a component's outgoing channel and matches replies to them
+#[derive(Clone, Debug)]
+pub struct Requester {
+    /// Container name written into requests, i.e. where replies are sent
+    sender_name: String,
+    comp_out_tx: Sender<ClientBrokerMessage<ContainerMessage>>,
+    next_correlation_id: StdArc<AtomicU64>,
+    pending: StdArc<Mutex<PendingRequests>>
+}
+
+/// Forgets a request once it is no longer awaited, whether it was answered, timed out or cancelled
+struct PendingGuard<'a> {
+    requester: &'a Requester,
+    correlation_id: u64
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.requester.pending_requests().remove(&self.correlation_id);
+    }
+}
+
+impl Requester {
+    /// Creates a requester for a component
+    ///
+    /// # Arguments
+    ///
+    /// * `sender_name` - This container's name as given in network_topology.xml
+    /// * `comp_out_tx` - The component's sender for outgoing broker messages
+    pub fn new(sender_name: &str, comp_out_tx: Sender<ClientBrokerMessage<ContainerMessage>>) -> Self {
+        Self {
+            sender_name: sender_name.to_string(),
+            comp_out_tx,
+            next_correlation_id: StdArc::new(AtomicU64::new(1)),
+            pending: StdArc::new(Mutex::new(HashMap::new()))
+        }
+    }
+
+    /// Sends a message to another container and waits for its reply
+    ///
+    /// Dropping the returned future cancels the request, and a reply arriving later is handed back by
+    /// [`Requester::resolve`] like any other unclaimed envelope.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Container to send the request to
+    /// * `message` - The request payload
+    /// * `timeout_after` - How long to wait for the reply
+    pub async fn request(&self, target: ContainerId, message: ContainerMessage, timeout_after: Duration) -> Result<Reply, RequestError> {
+        let correlation_id = self.next_correlation_id.fetch_add(1, Ordering::Relaxed);
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.pending_requests().insert(correlation_id, (target.as_str().to_string(), reply_tx));
+        let _pending = PendingGuard { requester: self, correlation_id };
+
+        let envelope = Envelope::request(correlation_id, self.sender_name.clone(), message);
+        let broker_message = ContainerId::broker_message(&[target], ContainerMessage::Envelope(envelope));
+        let (broker_message, span) = trace::send(&self.sender_name, broker_message);
+        let from_location = format!("{} requester", self.sender_name);
+        let to_location = format!("{} Container", self.sender_name);
+        add_to_tx_with_retry(&self.comp_out_tx, &broker_message, &from_location, &to_location).instrument(span).await;
+
+        match timeout(timeout_after, reply_rx).await {
+            Ok(Ok(reply)) => Ok(reply),
+            Ok(Err(_)) => Err(RequestError::Abandoned { target }),
+            Err(_) => Err(RequestError::Timeout { target, timeout: timeout_after })
+        }
+    }
+
+    /// Hands a reply envelope to the request awaiting it
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Envelope>` - The envelope back if it doesn't answer a pending request, e.g. because the
+    ///   request has already timed out or the envelope comes from another container than the request's target
+    pub fn resolve(&self, envelope: Envelope) -> Option<Envelope> {
+        if !envelope.is_reply() {
+            return Some(envelope);
+        }
+        let mut pending = self.pending_requests();
+        match pending.get(&envelope.correlation_id) {
+            Some((target, _)) if *target == envelope.sender => {}
+            Some((target, _)) => {
+                log::warn!("{} ignored a reply to request {} from {}, it was sent to {}", self.sender_name,
+                    envelope.correlation_id, envelope.sender, target);
+                return Some(envelope);
+            }
+            None => return Some(envelope)
+        }
+        if let Some((_, reply_tx)) = pending.remove(&envelope.correlation_id) {
+            // The requester may have stopped waiting in the meantime, in which case the reply is dropped
+            let _ = reply_tx.send(*envelope.payload);
+        }
+        None
+    }
+
+    /// Locks the pending requests, recovering the map if a previous holder panicked
+    fn pending_requests(&self) -> std::sync::MutexGuard<'_, PendingRequests> {
+        self.pending.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use common_messages::messages::example_message::ExampleMessage;
+
+    fn example(value: u64) -> ContainerMessage {
+        ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), value))
+    }
+
+    /// A requester whose requests can be read back, as they would arrive at their target
+    fn requester() -> (Requester, mpsc::Receiver<ClientBrokerMessage<ContainerMessage>>) {
+        let (comp_out_tx, comp_out_rx) = mpsc::channel(10);
+        (Requester::new("ComponentA", comp_out_tx), comp_out_rx)
+    }
+
+    async fn next_request(comp_out_rx: &mut mpsc::Receiver<ClientBrokerMessage<ContainerMessage>>) -> Envelope {
+        let broker_message = comp_out_rx.recv().await.unwrap();
+        assert_eq!(broker_message.client_name_list, vec!["ComponentB".to_string()]);
+        match broker_message.message {
+            ContainerMessage::Envelope(envelope) => envelope,
+            other => panic!("expected an Envelope, got {:?}", other)
+        }
+    }
+
+    #[tokio::test]
+    async fn a_request_gets_its_targets_reply() {
+        let (requester, mut comp_out_rx) = requester();
+        let request = tokio::spawn({
+            let requester = requester.clone();
+            async move { requester.request(ContainerId::ComponentB, example(1), Duration::from_secs(5)).await }
+        });
+
+        let envelope = next_request(&mut comp_out_rx).await;
+        assert_eq!(envelope.reply_to.as_deref(), Some("ComponentA"));
+        // Requests aren't replies, and are handed back
+        assert!(requester.resolve(envelope.clone()).is_some());
+        assert!(requester.resolve(envelope.reply("ComponentB".to_string(), example(2))).is_none());
+
+        let reply = request.await.unwrap().unwrap();
+        assert!(matches!(reply, ContainerMessage::ExampleMessage(example) if example.value == 2));
+        assert!(requester.pending_requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_reply_from_another_container_is_ignored() {
+        let (requester, mut comp_out_rx) = requester();
+        let request = tokio::spawn({
+            let requester = requester.clone();
+            async move { requester.request(ContainerId::ComponentB, example(1), Duration::from_secs(5)).await }
+        });
+
+        let envelope = next_request(&mut comp_out_rx).await;
+        let forged = envelope.reply("ComponentC".to_string(), example(3));
+        assert!(requester.resolve(forged).is_some());
+        assert!(requester.resolve(envelope.reply("ComponentB".to_string(), example(2))).is_none());
+        let reply = request.await.unwrap().unwrap();
+        assert!(matches!(reply, ContainerMessage::ExampleMessage(example) if example.value == 2));
+    }
+
+    #[tokio::test]
+    async fn an_unanswered_request_times_out() {
+        let (requester, _comp_out_rx) = requester();
+        let result = requester.request(ContainerId::ComponentB, example(1), Duration::from_millis(10)).await;
+        assert!(matches!(result, Err(RequestError::Timeout { target: ContainerId::ComponentB, .. })));
+        assert!(requester.pending_requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_cancelled_request_is_forgotten() {
+        let (requester, mut comp_out_rx) = requester();
+        let request = tokio::spawn({
+            let requester = requester.clone();
+            async move { requester.request(ContainerId::ComponentB, example(1), Duration::from_secs(60)).await }
+        });
+
+        let envelope = next_request(&mut comp_out_rx).await;
+        assert_eq!(requester.pending_requests().len(), 1);
+        request.abort();
+        assert!(request.await.unwrap_err().is_cancelled());
+        assert!(requester.pending_requests().is_empty());
+        // A late reply is handed back like any other unclaimed envelope
+        assert!(requester.resolve(envelope.reply("ComponentB".to_string(), example(2))).is_some());
+    }
+}
@@ -0,0 +1,235 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! Typed dispatch of container messages to handlers.
+//!
+//! Rather than matching every `ContainerMessage` variant in one function, a component registers a
+//! handler per message type and the [`MessageRouter`] hands each incoming message to the handler for
+//! its variant. Handlers are plain structs, so they can live in their own modules and be shared
+//! between components, optionally grouped into a [`HandlerModule`].
+//!
+//! ```ignore
+//! let mut router = MessageRouter::new()
+//!     .on::<ExampleMessage, _>(ExampleMessageHandler::new())
+//!     .module(StandardHandlers::new(name, &topics));
+//! router.report_unhandled(name, &["ComponentDirectiveMsg"]);
+//! ```
+
+// Standard
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+// Package
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::messages::message_variant::MessageVariant;
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+
+use async_trait::async_trait;
+
+
+/// Optional message for Hyperion's client broker to send on to other containers
+pub type HandlerResult = Option<ClientBrokerMessage<ContainerMessage>>;
+
+/// Handles messages of type `M`, with access to the component's context `C`
+#[async_trait]
+pub trait Handles<M: Send + 'static, C: Sync = ()>: Send {
+    async fn handle(&mut self, message: M, context: &C) -> HandlerResult;
+}
+
+/// A group of handlers registered together, e.g. the handlers shared by every component
+pub trait HandlerModule<C> {
+    fn register(self, router: MessageRouter<C>) -> MessageRouter<C>;
+}
+
+/// A handler with its message type erased, so handlers of different types can be stored together
+#[async_trait]
+trait ErasedHandler<C: Sync>: Send {
+    async fn handle_message(&mut self, message: ContainerMessage, context: &C) -> HandlerResult;
+}
+
+/// Recovers the message type of a handler before calling it
+struct TypedHandler<M, H> {
+    handler: H,
+    message_type: PhantomData<fn(M)>
+}
+
+#[async_trait]
+impl<M, H, C> ErasedHandler<C> for TypedHandler<M, H>
+where
+    M: MessageVariant<ContainerMessage> + Send + 'static,
+    H: Handles<M, C>,
+    C: Sync
+{
+    async fn handle_message(&mut self, message: ContainerMessage, context: &C) -> HandlerResult {
+        match M::try_from(message) {
+            Ok(message) => self.handler.handle(message, context).await,
+            // The router only hands over messages of this handler's kind
+            Err(message) => {
+                log::error!("A {} handler was given a {} message", M::KIND, message.kind());
+                None
+            }
+        }
+    }
+}
+
+/// Dispatches each message to the handler registered for its variant
+pub struct MessageRouter<C = ()> {
+    handlers: HashMap<&'static str, Box<dyn ErasedHandler<C>>>
+}
+
+impl<C: Sync + 'static> Default for MessageRouter<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Sync + 'static> MessageRouter<C> {
+    pub fn new() -> Self {
+        Self { handlers: HashMap::new() }
+    }
+
+    /// Registers the handler for messages of type `M`, replacing any previous handler
+    pub fn on<M, H>(mut self, handler: H) -> Self
+    where
+        M: MessageVariant<ContainerMessage> + Send + 'static,
+        H: Handles<M, C> + 'static
+    {
+        let typed_handler = TypedHandler { handler, message_type: PhantomData::<fn(M)> };
+        if self.handlers.insert(M::KIND, Box::new(typed_handler)).is_some() {
+            log::debug!("The handler for {} messages has been replaced", M::KIND);
+        }
+        self
+    }
+
+    /// Registers every handler of a module
+    pub fn module<Module: HandlerModule<C>>(self, module: Module) -> Self {
+        module.register(self)
+    }
+
+    /// Whether a handler is registered for the message kind
+    pub fn handles(&self, kind: &str) -> bool {
+        self.handlers.contains_key(kind)
+    }
+
+    /// Known message kinds without a registered handler
+    ///
+    /// # Arguments
+    ///
+    /// * `handled_elsewhere` - Kinds the component deals with itself rather than through the router
+    pub fn unhandled_kinds(&self, handled_elsewhere: &[&str]) -> Vec<&'static str> {
+        ContainerMessage::KINDS.iter()
+            .filter(|kind| !self.handles(kind) && !handled_elsewhere.contains(kind))
+            .copied()
+            .collect()
+    }
+
+    /// Logs the known message kinds nothing will handle, so gaps show up at startup rather than in traffic
+    pub fn report_unhandled(&self, container_name: &str, handled_elsewhere: &[&str]) {
+        let unhandled = self.unhandled_kinds(handled_elsewhere);
+        if !unhandled.is_empty() {
+            log::warn!("{} has no handler for these messages, which will be ignored: {}", container_name, unhandled.join(", "));
+        }
+    }
+
+    /// Hands a message to the handler for its variant
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HandlerResult, ContainerMessage>` - The handler's result, or the message back if no handler is
+    ///   registered or it is an `UnknownMessage`
+    pub async fn dispatch(&mut self, message: ContainerMessage, context: &C) -> Result<HandlerResult, ContainerMessage> {
+        // Undecoded messages may name a known kind, but can't be given to its handler
+        if let ContainerMessage::UnknownMessage(_) = message {
+            return Err(message);
+        }
+        match self.handlers.get_mut(message.kind()) {
+            Some(handler) => Ok(handler.handle_message(message, context).await),
+            None => Err(message)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_messages::messages::another_example_message::AnotherExampleMessage;
+    use common_messages::messages::example_message::ExampleMessage;
+    use common_messages::messages::unknown_message::UnknownMessage;
+
+    /// Sends every ExampleMessage on to the container named by the context
+    struct Echo;
+
+    #[async_trait]
+    impl Handles<ExampleMessage, &'static str> for Echo {
+        async fn handle(&mut self, example_message: ExampleMessage, context: &&'static str) -> HandlerResult {
+            Some(ClientBrokerMessage::new(vec![context], ContainerMessage::ExampleMessage(example_message)))
+        }
+    }
+
+    struct EchoModule;
+
+    impl HandlerModule<&'static str> for EchoModule {
+        fn register(self, router: MessageRouter<&'static str>) -> MessageRouter<&'static str> {
+            router.on::<ExampleMessage, _>(Echo)
+        }
+    }
+
+    fn example(value: u64) -> ContainerMessage {
+        ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), value))
+    }
+
+    #[tokio::test]
+    async fn a_message_is_dispatched_to_the_handler_for_its_kind() {
+        let mut router = MessageRouter::new().on::<ExampleMessage, _>(Echo);
+        let result = router.dispatch(example(41), &"ComponentB").await.unwrap().unwrap();
+        assert_eq!(result.client_name_list, vec!["ComponentB".to_string()]);
+        assert!(matches!(result.message, ContainerMessage::ExampleMessage(ExampleMessage { value: 41, .. })));
+    }
+
+    #[tokio::test]
+    async fn messages_without_a_handler_are_given_back() {
+        let mut router = MessageRouter::new().on::<ExampleMessage, _>(Echo);
+        let another = ContainerMessage::AnotherExampleMessage(AnotherExampleMessage::default());
+        assert!(matches!(router.dispatch(another, &"ComponentB").await, Err(ContainerMessage::AnotherExampleMessage(_))));
+        // An undecoded message naming a handled kind isn't handed to that handler
+        let unknown = ContainerMessage::UnknownMessage(UnknownMessage {
+            kind: "ExampleMessage".to_string(),
+            protocol_version: 0,
+            payload: serde_json::json!({"value": "not a number"}),
+            reason: "invalid type".to_string()
+        });
+        assert!(matches!(router.dispatch(unknown, &"ComponentB").await, Err(ContainerMessage::UnknownMessage(_))));
+    }
+
+    #[test]
+    fn a_module_registers_its_handlers() {
+        let router = MessageRouter::new().module(EchoModule);
+        assert!(router.handles("ExampleMessage"));
+        assert!(!router.handles("AnotherExampleMessage"));
+    }
+
+    #[test]
+    fn unhandled_kinds_are_the_known_kinds_nothing_handles() {
+        let router = MessageRouter::<&'static str>::new().on::<ExampleMessage, _>(Echo);
+        let unhandled = router.unhandled_kinds(&["ContainerDirectiveMsg"]);
+        assert_eq!(unhandled.len(), ContainerMessage::KINDS.len() - 2);
+        assert!(!unhandled.contains(&"ExampleMessage"));
+        assert!(!unhandled.contains(&"ContainerDirectiveMsg"));
+        assert!(unhandled.contains(&"AnotherExampleMessage"));
+
+        let handled_elsewhere: Vec<&str> = ContainerMessage::KINDS.iter().copied()
+            .filter(|kind| *kind != "ExampleMessage")
+            .collect();
+        assert!(router.unhandled_kinds(&handled_elsewhere).is_empty());
+        router.report_unhandled("ComponentA", &handled_elsewhere);
+    }
+}
@@ -0,0 +1,23 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Package
+use common_messages::routing::container_id::ContainerId;
+
+
+/// Declares the containers a component sends messages to.
+///
+/// The runtime checks these against the component's `client_connections` in network_topology.xml before
+/// the container starts, so a target missing from the topology fails loudly instead of silently dropping
+/// traffic.
+pub trait RoutingTargets {
+    fn routing_targets() -> &'static [ContainerId];
+}
@@ -0,0 +1,357 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Standard
+use std::fmt::Debug;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc as StdArc;
+
+// Package
+use common_messages::messages::config_reload_message::ConfigReloadMessage;
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::routing::container_id::ContainerId;
+use hyperion_framework::messages::container_directive::ContainerDirective;
+use hyperion_framework::containerisation::container_state::ContainerState;
+use hyperion_framework::containerisation::hyperion_container::HyperionContainer;
+use hyperion_framework::containerisation::hyperion_container_factory;
+use hyperion_framework::containerisation::traits::{ContainerIdentidy, Initialisable, LogLevel, Run};
+use hyperion_framework::utilities::tx_sender::add_to_tx_with_retry;
+use serde::de::DeserializeOwned;
+
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin, stdin};
+use tokio::sync::{mpsc, Notify};
+use tokio::time::{sleep, Duration};
+use tokio::task;
+
+// Local
+use crate::admin::start_admin;
+use crate::args::{self, CONFIG_ENV_VAR, LaunchArgs, TOPOLOGY_ENV_VAR, USAGE};
+use crate::authentication::{enable_authentication, sign_directive};
+use crate::cli::{CliCommand, CommandOutcome, CommandRegistry};
+use crate::discovery::discover_peers;
+use crate::logging::start_logging;
+use crate::error::RuntimeError;
+use crate::metrics;
+use crate::routing::RoutingTargets;
+use crate::signals::{ControlSignal, SignalListener};
+use crate::template::{InstanceVars, RenderedDir};
+use crate::tls::secure_links;
+use crate::topology::NetworkTopology;
+use crate::trace::{flush_traces, start_tracing};
+
+
+/// Default buffer size of the main container channel
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 32;
+/// Default delay between the container closing and the process exiting
+pub const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_millis(500);
+
+/// Line reader over the process' standard input
+type Console = Lines<BufReader<Stdin>>;
+
+/// Builder for running a component inside a Hyperion container.
+///
+/// Owns the bootstrap that every example component shares: container state management, the main
+/// message channel, the container factory and the command line control interface.
+///
+/// The config paths given here are defaults: `--config`/`--topology` on the command line, or the
+/// `HYPERION_CONFIG`/`HYPERION_TOPOLOGY` environment variables, take precedence so the same binary can
+/// be deployed with different configs. `--instance` fills the placeholders of templated config files
+/// (see [`crate::template`]) so several instances of one component can run side by side. Passing
+/// `--daemon` on the command line runs the container without the console, as a service controlled
+/// through signals: SIGTERM/SIGINT shut it down and SIGHUP reloads the configuration.
+///
+/// ```ignore
+/// RuntimeBuilder::new("component_a")
+///     .command(CliCommand::new("run_example", "Begin messages between components example",
+///         || ContainerMessage::ExampleMessage(ExampleMessage::default())))
+///     .run::<Component, Config>()
+///     .await;
+/// ```
+pub struct RuntimeBuilder {
+    config_path: String,
+    topology_path: String,
+    channel_capacity: usize,
+    commands: CommandRegistry,
+    shutdown_grace_period: Duration,
+    daemon: bool,
+    instance_vars: InstanceVars
+}
+
+impl RuntimeBuilder {
+    /// Creates a builder using the conventional config layout of a component directory,
+    /// i.e. `<component_dir>/config/configuration.xml` and `<component_dir>/config/network_topology.xml`
+    pub fn new(component_dir: &str) -> Self {
+        Self {
+            config_path: format!("{}/config/configuration.xml", component_dir),
+            topology_path: format!("{}/config/network_topology.xml", component_dir),
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            commands: CommandRegistry::with_defaults(),
+            shutdown_grace_period: DEFAULT_SHUTDOWN_GRACE_PERIOD,
+            daemon: false,
+            instance_vars: InstanceVars::default()
+        }
+    }
+
+    /// Sets the default container configuration file
+    pub fn config_path(mut self, path: &str) -> Self {
+        self.config_path = path.to_string();
+        self
+    }
+
+    /// Sets the default network topology configuration file
+    pub fn topology_path(mut self, path: &str) -> Self {
+        self.topology_path = path.to_string();
+        self
+    }
+
+    /// Sets the buffer size of the main container channel
+    pub fn channel_capacity(mut self, capacity: usize) -> Self {
+        self.channel_capacity = capacity;
+        self
+    }
+
+    /// Sets how long to wait for final messages to be processed once the container has closed
+    pub fn shutdown_grace_period(mut self, grace_period: Duration) -> Self {
+        self.shutdown_grace_period = grace_period;
+        self
+    }
+
+    /// Runs without the stdin console, e.g. under systemd or in a container with stdin closed.
+    /// Passing `--daemon` on the command line has the same effect.
+    pub fn daemon(mut self, daemon: bool) -> Self {
+        self.daemon = daemon;
+        self
+    }
+
+    /// Adds a component specific console command, replacing a default command of the same name
+    pub fn command(mut self, command: CliCommand) -> Self {
+        self.commands.register(command);
+        self
+    }
+
+    /// Sets up and runs the Hyperion container with the CLI control interface.
+    ///
+    /// Returns only by exiting the process, once the container has closed or immediately if the
+    /// command line or config files are invalid.
+    pub async fn run<C, Cfg>(mut self)
+    where
+        C: Initialisable<ConfigType = Cfg> + Run<Message = ContainerMessage> + RoutingTargets + Send + 'static,
+        Cfg: Debug + DeserializeOwned + ContainerIdentidy + LogLevel + Send + Sync + 'static
+    {
+        if let Err(error) = self.apply_launch_args() {
+            eprintln!("Unable to start the container: {}", error);
+            eprintln!("{}", USAGE);
+            std::process::exit(1);
+        }
+
+        // Initialize container state management
+        // Uses atomic operations for thread-safe state changes
+        let container_state: StdArc<AtomicUsize> = StdArc::new(AtomicUsize::new(ContainerState::Running as usize));
+        let container_state_notify: StdArc<Notify> = StdArc::new(Notify::new());
+
+        // Set up message channel for container communication
+        let (main_tx, main_rx) = mpsc::channel::<ContainerMessage>(self.channel_capacity);
+        metrics::watch_channel("main", &main_tx);
+
+        // Holds the rendered config files until the process exits, which skips destructors, so it is dropped first
+        let rendered_dir = match RenderedDir::create() {
+            Ok(rendered_dir) => rendered_dir,
+            Err(error) => {
+                eprintln!("Unable to start the container: {}", error);
+                std::process::exit(1);
+            }
+        };
+
+        // Fill in templated config files, set up logging, authentication and tracing, discover peers and check the topology
+        let (config_path, topology_path) = match self.prepare_config_files::<Cfg>(C::routing_targets(), &rendered_dir).await {
+            Ok(paths) => paths,
+            Err(error) => {
+                eprintln!("Unable to start the container: {}", error);
+                drop(rendered_dir);
+                std::process::exit(1);
+            }
+        };
+
+        // Initialize and configure the Hyperion container
+        let mut container: HyperionContainer<ContainerMessage> =
+            hyperion_container_factory::create::<C, Cfg, ContainerMessage>(
+                &config_path,
+                &topology_path,
+                container_state.clone(),
+                container_state_notify.clone(),
+                main_rx
+            ).await;
+
+        // Let scripts and orchestrators probe and control the container over HTTP, if configured
+        if let Err(error) = start_admin::<Cfg>(&config_path, main_tx.clone(), container_state.clone()).await {
+            eprintln!("Unable to start the container: {}", error);
+            drop(rendered_dir);
+            std::process::exit(1);
+        }
+
+        // Spawn the container in a separate task
+        task::spawn(async move {
+            container.run().await;
+        });
+
+        // Control signals are always honoured; the console only when running interactively
+        let mut signals = match SignalListener::new() {
+            Ok(signals) => Some(signals),
+            Err(error) => {
+                log::error!("Unable to listen for control signals: {}", error);
+                None
+            }
+        };
+        let mut console: Option<Console> = if self.daemon {
+            log::info!("Running in daemon mode: send SIGTERM to shut down or SIGHUP to reload the configuration.");
+            None
+        } else {
+            log::info!("Enter a command or press h for help.");
+            Some(BufReader::new(stdin()).lines())
+        };
+
+        // Main command processing loop
+        loop {
+            tokio::select! {
+                // Handle CLI commands
+                line = next_console_line(&mut console), if console.is_some() => {
+                    match line {
+                        Ok(Some(line)) => self.handle_console_line(&main_tx, &line).await,
+                        Ok(None) => {
+                            log::warn!("Standard input has closed, the console is disabled. Use signals to control the container.");
+                            console = None;
+                        }
+                        Err(error) => {
+                            log::error!("Unable to read from standard input, the console is disabled: {}", error);
+                            console = None;
+                        }
+                    }
+                }
+                // Handle control signals from the operating system
+                signal = next_signal(&mut signals), if signals.is_some() => {
+                    let message = match signal {
+                        ControlSignal::Shutdown => {
+                            log::info!("Shutdown signal received");
+                            ContainerMessage::ContainerDirectiveMsg(ContainerDirective::Shutdown)
+                        }
+                        ControlSignal::Reload => {
+                            log::info!("Reload signal received, reloading {}", self.config_path);
+                            match self.instance_vars.render_file(&self.config_path, &rendered_dir) {
+                                Ok(config_path) => ContainerMessage::ConfigReloadMessage(ConfigReloadMessage::new(config_path)),
+                                Err(error) => {
+                                    log::error!("Configuration was not reloaded: {}", error);
+                                    continue;
+                                }
+                            }
+                        }
+                    };
+                    add_to_tx_with_retry(&main_tx, &sign_directive(message), "Signal handler", "Container main").await;
+                }
+                // Monitor container state for shutdown
+                _ = container_state_notify.notified() => {
+                    if container_state.load(Ordering::SeqCst) == ContainerState::Closed as usize {
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Clean shutdown procedure
+        drop(console);
+        log::info!("Hyperion Container closed gracefully");
+        // Brief delay to allow final messages to be processed
+        sleep(self.shutdown_grace_period).await;
+        flush_traces();
+        drop(rendered_dir);
+        std::process::exit(0);
+    }
+
+    /// Applies the command line and environment to the builder and checks the config files exist
+    fn apply_launch_args(&mut self) -> Result<(), RuntimeError> {
+        let launch_args = LaunchArgs::from_env()?.with_env_instance()?;
+        if launch_args.help {
+            println!("{}", USAGE);
+            std::process::exit(0);
+        }
+        self.daemon |= launch_args.daemon;
+
+        let (config_path, config_origin) =
+            args::resolve_path(launch_args.config_path.as_deref(), "--config", CONFIG_ENV_VAR, &self.config_path);
+        let (topology_path, topology_origin) =
+            args::resolve_path(launch_args.topology_path.as_deref(), "--topology", TOPOLOGY_ENV_VAR, &self.topology_path);
+        for (path, origin) in [(&config_path, config_origin), (&topology_path, topology_origin)] {
+            if !Path::new(path).is_file() {
+                return Err(RuntimeError::MissingFile { path: path.clone(), origin });
+            }
+        }
+
+        self.config_path = config_path;
+        self.topology_path = topology_path;
+        self.instance_vars = InstanceVars::new(launch_args.instance, launch_args.port_offset);
+        Ok(())
+    }
+
+    /// Renders the config files for this instance, installs the logger, sets up message authentication and tracing and
+    /// discovers peers if enabled, checks the component's routing targets exist and starts the TLS proxies if enabled
+    ///
+    /// # Returns
+    ///
+    /// * `(String, String)` - Paths of the configuration and network topology files to hand to Hyperion
+    async fn prepare_config_files<Cfg>(&self, routing_targets: &[ContainerId], rendered_dir: &RenderedDir)
+        -> Result<(String, String), RuntimeError>
+    where
+        Cfg: DeserializeOwned + ContainerIdentidy
+    {
+        let config_path = self.instance_vars.render_file(&self.config_path, rendered_dir)?;
+        let mut topology_path = self.instance_vars.render_file(&self.topology_path, rendered_dir)?;
+        let mut topology = NetworkTopology::load(&topology_path)?;
+        start_logging(&config_path, &topology.container_name)?;
+        enable_authentication(&config_path, &topology.container_name)?;
+        start_tracing(&config_path, &topology.container_name)?;
+        let discovered = discover_peers::<Cfg>(&config_path, &mut topology, routing_targets).await?;
+        topology.check_routing_targets(routing_targets, &self.topology_path)?;
+        // Hyperion connects through the TLS proxies, while the rest of the runtime sees the peers' own addresses
+        match secure_links(&topology).await? {
+            Some(hyperion_topology) => topology_path = hyperion_topology.write_generated(rendered_dir)?,
+            None if discovered => topology_path = topology.write_generated(rendered_dir)?,
+            None => {}
+        }
+        topology.set_current();
+        Ok((config_path, topology_path))
+    }
+
+    /// Sends the message produced by a console line, or prints the console's response
+    async fn handle_console_line(&self, main_tx: &mpsc::Sender<ContainerMessage>, line: &str) {
+        match self.commands.dispatch(line) {
+            CommandOutcome::Send(message) => {
+                add_to_tx_with_retry(main_tx, &sign_directive(message), "Command line", "Container main").await;
+            }
+            CommandOutcome::Print(text) => println!("{}", text)
+        }
+    }
+}
+
+/// Reads the next console line, never resolving once the console has been disabled
+async fn next_console_line(console: &mut Option<Console>) -> std::io::Result<Option<String>> {
+    match console {
+        Some(console) => console.next_line().await,
+        None => std::future::pending().await
+    }
+}
+
+/// Waits for the next control signal, never resolving if signals are unavailable
+async fn next_signal(signals: &mut Option<SignalListener>) -> ControlSignal {
+    match signals {
+        Some(signals) => signals.recv().await,
+        None => std::future::pending().await
+    }
+}
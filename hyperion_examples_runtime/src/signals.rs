@@ -0,0 +1,77 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! Operating system signals used to control a container that runs without a console.
+
+// Standard
+use std::io;
+
+
+/// Control requests delivered by the operating system
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlSignal {
+    /// SIGTERM or SIGINT (Ctrl+C): shut the container down gracefully
+    Shutdown,
+    /// SIGHUP: reload the component configuration
+    Reload
+}
+
+/// Listens for the signals a service manager uses to control the process
+#[cfg(unix)]
+pub struct SignalListener {
+    terminate: tokio::signal::unix::Signal,
+    interrupt: tokio::signal::unix::Signal,
+    hangup: tokio::signal::unix::Signal
+}
+
+#[cfg(unix)]
+impl SignalListener {
+    /// Registers the signal handlers, replacing the default terminate behaviour
+    pub fn new() -> io::Result<Self> {
+        use tokio::signal::unix::{signal, SignalKind};
+        Ok(Self {
+            terminate: signal(SignalKind::terminate())?,
+            interrupt: signal(SignalKind::interrupt())?,
+            hangup: signal(SignalKind::hangup())?
+        })
+    }
+
+    /// Waits for the next control signal
+    pub async fn recv(&mut self) -> ControlSignal {
+        tokio::select! {
+            _ = self.terminate.recv() => ControlSignal::Shutdown,
+            _ = self.interrupt.recv() => ControlSignal::Shutdown,
+            _ = self.hangup.recv() => ControlSignal::Reload
+        }
+    }
+}
+
+/// Listens for Ctrl+C, the only control signal available on this platform
+#[cfg(not(unix))]
+pub struct SignalListener;
+
+#[cfg(not(unix))]
+impl SignalListener {
+    pub fn new() -> io::Result<Self> {
+        Ok(Self)
+    }
+
+    /// Waits for the next control signal
+    pub async fn recv(&mut self) -> ControlSignal {
+        match tokio::signal::ctrl_c().await {
+            Ok(()) => ControlSignal::Shutdown,
+            Err(error) => {
+                log::error!("Unable to listen for Ctrl+C: {}", error);
+                std::future::pending().await
+            }
+        }
+    }
+}
@@ -0,0 +1,280 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Component state snapshots, written on a WriteToFile directive and restored at startup.
+
+// Standard
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Package
+use common_messages::messages::container_message::{local, ContainerMessage};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+// Local
+use crate::error::RuntimeError;
+
+
+/// Number of recent messages kept in a snapshot when the configuration doesn't say
+pub const DEFAULT_SNAPSHOT_LAST_MESSAGES: usize = 10;
+
+/// File format of snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum SnapshotFormat {
+    #[default]
+    Json,
+    Ron
+}
+
+impl SnapshotFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            SnapshotFormat::Json => "json",
+            SnapshotFormat::Ron => "ron"
+        }
+    }
+}
+
+/// The `<snapshot>` section of a component's configuration.xml
+#[derive(Debug, Clone, Deserialize)]
+pub struct SnapshotConfig {
+    /// Directory snapshots are written to and restored from
+    #[serde(default = "default_directory")]
+    pub directory: String,
+    #[serde(default)]
+    pub format: SnapshotFormat,
+    /// Whether the component resumes from its latest snapshot when it starts
+    #[serde(default)]
+    pub restore_on_start: bool,
+    /// Number of recently processed messages kept in a snapshot
+    #[serde(default = "default_last_messages")]
+    pub last_messages: usize
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            directory: default_directory(),
+            format: SnapshotFormat::default(),
+            restore_on_start: false,
+            last_messages: DEFAULT_SNAPSHOT_LAST_MESSAGES
+        }
+    }
+}
+
+fn default_directory() -> String {
+    "snapshots".to_string()
+}
+
+fn default_last_messages() -> usize {
+    DEFAULT_SNAPSHOT_LAST_MESSAGES
+}
+
+/// State specific to a component that is kept in its snapshots
+pub trait Snapshot {
+    type State: Serialize + DeserializeOwned;
+
+    /// Captures the component's own state
+    fn snapshot_state(&self) -> Self::State;
+
+    /// Resumes from state captured by a previous run
+    fn restore_state(&mut self, state: Self::State);
+}
+
+/// Everything written to a snapshot file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComponentSnapshot<S> {
+    /// Identity of the container that wrote the snapshot, from its configuration
+    pub identity: BTreeMap<String, String>,
+    /// Milliseconds since the Unix epoch when the snapshot was taken
+    pub taken_at_ms: u64,
+    /// Messages processed since the component was first started, including previous runs
+    pub messages_processed: u64,
+    /// The most recently processed messages, oldest first
+    #[serde(deserialize_with = "local::deserialize_all")]
+    pub last_messages: Vec<ContainerMessage>,
+    /// State specific to the component
+    pub state: S
+}
+
+/// Counts the messages a component processes and remembers the most recent ones for its snapshots
+#[derive(Debug, Clone)]
+pub struct MessageHistory {
+    messages_processed: u64,
+    last_messages: VecDeque<ContainerMessage>,
+    capacity: usize
+}
+
+impl MessageHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self { messages_processed: 0, last_messages: VecDeque::new(), capacity }
+    }
+
+    pub fn record(&mut self, message: &ContainerMessage) {
+        self.messages_processed += 1;
+        if self.capacity == 0 { return; }
+        if self.last_messages.len() >= self.capacity {
+            self.last_messages.pop_front();
+        }
+        self.last_messages.push_back(message.clone());
+    }
+
+    /// Builds a snapshot from the recorded messages and the component's own state
+    pub fn snapshot<S>(&self, identity: HashMap<String, String>, state: S) -> ComponentSnapshot<S> {
+        ComponentSnapshot {
+            identity: identity.into_iter().collect(),
+            taken_at_ms: now_ms(),
+            messages_processed: self.messages_processed,
+            last_messages: self.last_messages.iter().cloned().collect(),
+            state
+        }
+    }
+
+    /// Carries on counting from a restored snapshot
+    pub fn restore<S>(&mut self, snapshot: &ComponentSnapshot<S>) {
+        self.messages_processed = snapshot.messages_processed;
+        let skip = snapshot.last_messages.len().saturating_sub(self.capacity);
+        self.last_messages = snapshot.last_messages.iter().skip(skip).cloned().collect();
+    }
+}
+
+/// Writes a snapshot to `<directory>/<container name>-<milliseconds since epoch>.<json|ron>`
+///
+/// # Returns
+///
+/// * `Result<PathBuf, RuntimeError>` - Path of the written file
+pub fn write_snapshot<S: Serialize>(config: &SnapshotConfig, container_name: &str, snapshot: &ComponentSnapshot<S>)
+    -> Result<PathBuf, RuntimeError> {
+    let path = Path::new(&config.directory)
+        .join(format!("{}-{}.{}", container_name, snapshot.taken_at_ms, config.format.extension()));
+    let path_text = path.display().to_string();
+    let contents = match config.format {
+        SnapshotFormat::Json => serde_json::to_string_pretty(snapshot).map_err(|error| error.to_string()),
+        SnapshotFormat::Ron => ron::ser::to_string_pretty(snapshot, ron::ser::PrettyConfig::default())
+            .map_err(|error| error.to_string())
+    }.map_err(|message| RuntimeError::Serialize { path: path_text.clone(), message })?;
+
+    fs::create_dir_all(&config.directory)
+        .map_err(|source| RuntimeError::Write { path: config.directory.clone(), source })?;
+    fs::write(&path, contents).map_err(|source| RuntimeError::Write { path: path_text, source })?;
+    Ok(path)
+}
+
+/// Reads the most recent snapshot written by the named container in the configured format
+///
+/// # Returns
+///
+/// * `Result<Option<(PathBuf, ComponentSnapshot<S>)>, RuntimeError>` - None when no snapshot has been written yet
+pub fn latest_snapshot<S: DeserializeOwned>(config: &SnapshotConfig, container_name: &str)
+    -> Result<Option<(PathBuf, ComponentSnapshot<S>)>, RuntimeError> {
+    let entries = match fs::read_dir(&config.directory) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(None)
+    };
+    let prefix = format!("{}-", container_name);
+    let latest = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == config.format.extension()))
+        .filter_map(|path| {
+            let taken_at_ms = path.file_stem()?.to_str()?.strip_prefix(&prefix)?.parse::<u64>().ok()?;
+            Some((taken_at_ms, path))
+        })
+        .max_by_key(|(taken_at_ms, _)| *taken_at_ms);
+    let Some((_, path)) = latest else { return Ok(None) };
+
+    let path_text = path.display().to_string();
+    let contents = fs::read_to_string(&path)
+        .map_err(|source| RuntimeError::Io { path: path_text.clone(), source })?;
+    let snapshot = match config.format {
+        SnapshotFormat::Json => serde_json::from_str(&contents).map_err(|error| error.to_string()),
+        SnapshotFormat::Ron => ron::from_str(&contents).map_err(|error| error.to_string())
+    }.map_err(|message| RuntimeError::Parse { path: path_text, message })?;
+    Ok(Some((path, snapshot)))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_messages::messages::example_message::ExampleMessage;
+    use common_messages::messages::reliable::Reliable;
+    use common_messages::messages::traced::Traced;
+
+    /// Shaped like the example components' snapshot state
+    #[derive(Debug, Serialize, Deserialize)]
+    struct HeldState {
+        dormant: bool,
+        #[serde(deserialize_with = "local::deserialize_all")]
+        held_messages: Vec<ContainerMessage>
+    }
+
+    fn example(value: u64) -> ContainerMessage {
+        ContainerMessage::ExampleMessage(ExampleMessage::new(format!("message {}", value), value))
+    }
+
+    fn round_trip(format: SnapshotFormat) {
+        let directory = std::env::temp_dir().join(format!("hyperion_snapshots_{:?}_{}", format, std::process::id()));
+        let config = SnapshotConfig { directory: directory.display().to_string(), format, ..SnapshotConfig::default() };
+        let mut history = MessageHistory::new(2);
+        for value in 1..=3 {
+            history.record(&example(value));
+        }
+        let held_messages = vec![
+            example(4),
+            ContainerMessage::Reliable(Reliable::new(7, "ComponentA".to_string(), example(5))),
+            ContainerMessage::Traced(Traced::new("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+                example(6)))
+        ];
+        let identity = HashMap::from([("version".to_string(), "1.0.0".to_string())]);
+        let snapshot = history.snapshot(identity, HeldState { dormant: true, held_messages });
+        let path = write_snapshot(&config, "ComponentA", &snapshot).unwrap();
+
+        let latest = latest_snapshot::<HeldState>(&config, "ComponentA");
+        let _ = fs::remove_dir_all(&directory);
+        let (latest_path, restored) = latest.unwrap().unwrap();
+        assert_eq!(latest_path, path);
+        assert_eq!(restored.identity["version"], "1.0.0");
+        assert_eq!(restored.messages_processed, 3);
+        let values: Vec<u64> = restored.last_messages.iter().map(|message| match message {
+            ContainerMessage::ExampleMessage(example) => example.value,
+            other => panic!("expected an ExampleMessage, got {:?}", other)
+        }).collect();
+        assert_eq!(values, vec![2, 3]);
+
+        assert!(restored.state.dormant);
+        let [held, reliable, traced] = &restored.state.held_messages[..] else {
+            panic!("expected three held messages, got {:?}", restored.state.held_messages);
+        };
+        assert!(matches!(held, ContainerMessage::ExampleMessage(example) if example.value == 4));
+        assert!(matches!(reliable, ContainerMessage::Reliable(reliable) if reliable.message_id == 7
+            && matches!(*reliable.payload, ContainerMessage::ExampleMessage(ref example) if example.value == 5)));
+        assert!(matches!(traced, ContainerMessage::Traced(traced)
+            if matches!(*traced.payload, ContainerMessage::ExampleMessage(ref example) if example.value == 6)));
+    }
+
+    #[test]
+    fn a_ron_snapshot_keeps_its_held_messages() {
+        round_trip(SnapshotFormat::Ron);
+    }
+
+    #[test]
+    fn a_json_snapshot_keeps_its_held_messages() {
+        round_trip(SnapshotFormat::Json);
+    }
+}
@@ -0,0 +1,271 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! Instance templating for configuration files.
+//!
+//! Running several instances of one component binary needs distinct container names and ports. Rather
+//! than hand-copying config directories, configuration.xml and network_topology.xml may contain
+//! placeholders that are filled in per instance:
+//!
+//! * `${INSTANCE}` - the `--instance` id, or `0` when none was given
+//! * `${INSTANCE_SUFFIX}` - `-<id>` for an instance, or nothing when none was given
+//! * `${PORT:<base>}` - the base port plus the instance's port offset
+//!
+//! e.g. `<server_address>127.0.0.1:${PORT:50502}</server_address>`
+//!
+//! Rendered copies are written to a [`RenderedDir`], which only the current user can read and which is removed
+//! when the container shuts down.
+
+// Standard
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Local
+use crate::error::RuntimeError;
+
+
+/// Start of a placeholder
+const PLACEHOLDER_START: &str = "${";
+/// End of a placeholder
+const PLACEHOLDER_END: char = '}';
+/// Start of the name of every process' directory under the system temp directory
+const RENDERED_DIR_PREFIX: &str = "hyperion_examples";
+/// Attempts at finding an unused directory name before giving up
+const RENDERED_DIR_ATTEMPTS: u32 = 16;
+
+/// Values substituted into the config files of one component instance
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InstanceVars {
+    /// Instance id, or `None` for the single default instance
+    pub instance: Option<String>,
+    /// Added to every `${PORT:<base>}` placeholder
+    pub port_offset: u16
+}
+
+impl InstanceVars {
+    /// Creates the variables for an instance, deriving the port offset from a numeric id unless given
+    pub fn new(instance: Option<String>, port_offset: Option<u16>) -> Self {
+        let port_offset = port_offset
+            .or_else(|| instance.as_deref().and_then(|id| id.parse::<u16>().ok()))
+            .unwrap_or(0);
+        Self { instance, port_offset }
+    }
+
+    /// Replaces every placeholder in `contents`
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path the contents were read from, named in errors
+    /// * `contents` - Text of the config file
+    pub fn render(&self, path: &str, contents: &str) -> Result<String, RuntimeError> {
+        let mut rendered = String::with_capacity(contents.len());
+        let mut rest = contents;
+
+        while let Some(start) = rest.find(PLACEHOLDER_START) {
+            rendered.push_str(&rest[..start]);
+            let after_start = &rest[start + PLACEHOLDER_START.len()..];
+            let end = after_start.find(PLACEHOLDER_END).ok_or_else(|| RuntimeError::Parse {
+                path: path.to_string(),
+                message: "unterminated `${` placeholder".to_string()
+            })?;
+            rendered.push_str(&self.value_of(path, &after_start[..end])?);
+            rest = &after_start[end + 1..];
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+
+    /// Value of a single placeholder
+    fn value_of(&self, path: &str, placeholder: &str) -> Result<String, RuntimeError> {
+        let invalid = |message: String| RuntimeError::Parse { path: path.to_string(), message };
+        match placeholder.split_once(':') {
+            None if placeholder == "INSTANCE" => Ok(self.instance.clone().unwrap_or_else(|| "0".to_string())),
+            None if placeholder == "INSTANCE_SUFFIX" => Ok(self.instance.as_ref()
+                .map(|id| format!("-{}", id))
+                .unwrap_or_default()),
+            Some(("PORT", base)) => {
+                let base: u16 = base.trim().parse()
+                    .map_err(|_| invalid(format!("`{}` is not a valid base port", base)))?;
+                base.checked_add(self.port_offset)
+                    .map(|port| port.to_string())
+                    .ok_or_else(|| invalid(format!("port {} plus offset {} is out of range", base, self.port_offset)))
+            }
+            _ => Err(invalid(format!("unknown placeholder `${{{}}}`", placeholder)))
+        }
+    }
+
+    /// Renders a config file for this instance
+    ///
+    /// Files without placeholders are used as they are. Otherwise the rendered copy is written to the
+    /// process' [`RenderedDir`] and its path is returned. Rendering the same file again, e.g. on a reload,
+    /// replaces its previous copy.
+    pub fn render_file(&self, path: &str, rendered_dir: &RenderedDir) -> Result<String, RuntimeError> {
+        let contents = fs::read_to_string(path)
+            .map_err(|source| RuntimeError::Io { path: path.to_string(), source })?;
+        if !contents.contains(PLACEHOLDER_START) {
+            return Ok(path.to_string());
+        }
+        let rendered = self.render(path, &contents)?;
+
+        let rendered_path = rendered_dir.rendered_path(Path::new(path));
+        fs::write(&rendered_path, rendered)
+            .map_err(|source| RuntimeError::Io { path: rendered_path.display().to_string(), source })?;
+        Ok(rendered_path.display().to_string())
+    }
+}
+
+/// A directory of this process' rendered and generated config files, removed when dropped
+///
+/// It is created under the system temp directory with a name no other process has used, and on Unix can only
+/// be read by the current user, as the config files it holds may name key and certificate files.
+#[derive(Debug)]
+pub struct RenderedDir {
+    path: PathBuf
+}
+
+impl RenderedDir {
+    /// Creates a new, empty directory under the system temp directory
+    pub fn create() -> Result<Self, RuntimeError> {
+        Self::create_in(&std::env::temp_dir())
+    }
+
+    /// Creates a new, empty directory in `parent`
+    pub fn create_in(parent: &Path) -> Result<Self, RuntimeError> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.subsec_nanos()).unwrap_or_default();
+        let mut last_error = None;
+        for attempt in 0..RENDERED_DIR_ATTEMPTS {
+            let path = parent.join(format!("{}-{}-{:08x}", RENDERED_DIR_PREFIX, std::process::id(), nanos.wrapping_add(attempt)));
+            match create_private_dir(&path) {
+                Ok(()) => return Ok(Self { path }),
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => last_error = Some(error),
+                Err(source) => return Err(RuntimeError::Write { path: path.display().to_string(), source })
+            }
+        }
+        Err(RuntimeError::Write {
+            path: parent.display().to_string(),
+            source: last_error.unwrap_or_else(|| ErrorKind::AlreadyExists.into())
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Where the rendered copy of a config file goes
+    ///
+    /// The name keeps the file's own name and adds a hash of its full path, so files with the same name in
+    /// different directories don't replace each other.
+    fn rendered_path(&self, source: &Path) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        fs::canonicalize(source).unwrap_or_else(|_| source.to_path_buf()).hash(&mut hasher);
+        let stem = source.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_else(|| "config".to_string());
+        let extension = source.extension().map(|extension| format!(".{}", extension.to_string_lossy())).unwrap_or_default();
+        self.path.join(format!("{}-{:016x}{}", stem, hasher.finish(), extension))
+    }
+}
+
+impl Drop for RenderedDir {
+    fn drop(&mut self) {
+        match fs::remove_dir_all(&self.path) {
+            Err(error) if error.kind() != ErrorKind::NotFound =>
+                log::warn!("Unable to remove {}: {}", self.path.display(), error),
+            _ => {}
+        }
+    }
+}
+
+/// Creates a directory only the current user can access, failing if it already exists
+#[cfg(unix)]
+fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    fs::DirBuilder::new().mode(0o700).create(path)
+}
+
+/// Creates a directory, failing if it already exists
+#[cfg(not(unix))]
+fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    fs::DirBuilder::new().create(path)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(id: &str) -> InstanceVars {
+        InstanceVars::new(Some(id.to_string()), None)
+    }
+
+    fn render(vars: &InstanceVars, contents: &str) -> Result<String, RuntimeError> {
+        vars.render("configuration.xml", contents)
+    }
+
+    #[test]
+    fn placeholders_default_to_the_single_instance() {
+        let vars = InstanceVars::default();
+        assert_eq!(render(&vars, "<name>ComponentB${INSTANCE_SUFFIX}</name>").unwrap(), "<name>ComponentB</name>");
+        assert_eq!(render(&vars, "${INSTANCE}:${PORT:50502}").unwrap(), "0:50502");
+    }
+
+    #[test]
+    fn placeholders_are_filled_in_per_instance() {
+        assert_eq!(render(&instance("1"), "ComponentB${INSTANCE_SUFFIX}@127.0.0.1:${PORT:50502}").unwrap(),
+            "ComponentB-1@127.0.0.1:50503");
+        // An explicit offset wins over a numeric id, and a named instance has no offset of its own
+        assert_eq!(render(&InstanceVars::new(Some("2".to_string()), Some(10)), "${PORT:50502}").unwrap(), "50512");
+        assert_eq!(render(&instance("blue"), "${INSTANCE}:${PORT: 50502 }").unwrap(), "blue:50502");
+        assert_eq!(render(&instance("1"), "no placeholders").unwrap(), "no placeholders");
+    }
+
+    #[test]
+    fn invalid_placeholders_are_rejected() {
+        let vars = InstanceVars::new(None, Some(10));
+        for contents in ["${PORT:port}", "${PORT:65530}", "${PORT}", "${HOST}", "${INSTANCE"] {
+            assert!(matches!(render(&vars, contents), Err(RuntimeError::Parse { .. })), "{} was accepted", contents);
+        }
+    }
+
+    #[test]
+    fn files_are_rendered_into_a_private_directory_removed_on_drop() {
+        let sources = RenderedDir::create().unwrap();
+        for component in ["component_a", "component_b"] {
+            fs::create_dir(sources.path().join(component)).unwrap();
+            fs::write(sources.path().join(component).join("configuration.xml"),
+                format!("<name>{}${{INSTANCE_SUFFIX}}</name>", component)).unwrap();
+        }
+        fs::write(sources.path().join("plain.xml"), "<name>plain</name>").unwrap();
+        let source = |name: &str| sources.path().join(name).display().to_string();
+
+        let rendered_dir = RenderedDir::create().unwrap();
+        let vars = instance("1");
+        let rendered_a = vars.render_file(&source("component_a/configuration.xml"), &rendered_dir).unwrap();
+        let rendered_b = vars.render_file(&source("component_b/configuration.xml"), &rendered_dir).unwrap();
+        assert_ne!(rendered_a, rendered_b);
+        assert_eq!(fs::read_to_string(&rendered_a).unwrap(), "<name>component_a-1</name>");
+        assert_eq!(fs::read_to_string(&rendered_b).unwrap(), "<name>component_b-1</name>");
+        // Rendering again replaces the earlier copy, and files without placeholders are used in place
+        assert_eq!(vars.render_file(&source("component_a/configuration.xml"), &rendered_dir).unwrap(), rendered_a);
+        assert_eq!(vars.render_file(&source("plain.xml"), &rendered_dir).unwrap(), source("plain.xml"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(rendered_dir.path()).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+        let path = rendered_dir.path().to_path_buf();
+        drop(rendered_dir);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,55 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Generation of a certificate authority and container certificates for trying out TLS locally.
+//!
+//! Only built with the `test-certs` feature, which the `generate_test_certs` binary needs, and for tests.
+
+// Standard
+use std::fs;
+use std::path::Path;
+
+// Package
+use rcgen::{BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair, KeyUsagePurpose};
+
+
+/// Common name of the generated certificate authority
+const CA_NAME: &str = "Hyperion Examples Test CA";
+
+
+/// Writes the certificate authority and the certificates of the given containers
+///
+/// # Arguments
+///
+/// * `directory` - Created if it does not exist; existing certificates are overwritten
+/// * `names` - Container names, each used as the subject and DNS name of its certificate
+pub fn generate_test_certs(directory: &Path, names: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(directory)?;
+
+    let mut ca_params = CertificateParams::new(Vec::<String>::new())?;
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    ca_params.distinguished_name.push(DnType::CommonName, CA_NAME);
+    ca_params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+    let ca_key = KeyPair::generate()?;
+    let ca_certificate = ca_params.self_signed(&ca_key)?;
+    fs::write(directory.join("ca.pem"), ca_certificate.pem())?;
+
+    for name in names {
+        // Peers check the container name against the certificate's DNS name
+        let mut params = CertificateParams::new(vec![name.clone()])?;
+        params.distinguished_name.push(DnType::CommonName, name.as_str());
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth, ExtendedKeyUsagePurpose::ClientAuth];
+        let key = KeyPair::generate()?;
+        let certificate = params.signed_by(&key, &ca_certificate, &ca_key)?;
+        fs::write(directory.join(format!("{}.pem", name)), certificate.pem())?;
+        fs::write(directory.join(format!("{}.key", name)), key.serialize_pem())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,384 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! TLS with mutual authentication for the links between containers.
+//!
+//! Hyperion's connections are plain TCP, so the runtime secures them with local proxies when the
+//! network_topology.xml has a `<tls>` section:
+//!
+//! * Other containers connect to the TLS `listen_address`. A connection is accepted once the peer presents
+//!   a certificate issued by the configured authority for one of the `<allowed_client>` containers (by default
+//!   those of the client connections, never this container itself), and is then forwarded to the Hyperion server
+//!   on `server_address`, which should only listen on the loopback interface.
+//! * Each client connection's address is the peer's TLS address. Hyperion is handed a topology pointing it
+//!   at a local port instead, whose connections are forwarded over TLS to the peer, and only once the peer's
+//!   certificate has been checked against the connection's container name.
+//!
+//!
+//! The container a connection was verified for is recorded in the installed
+//! [`VerifiedPeers`](common_messages::routing::peer_identity::VerifiedPeers) while it is open. With message
+//! authentication enabled, signed messages are then only accepted from containers with a verified connection;
+//! without it, messages carry no sender that could be checked.
+//!
+//! Certificates for local testing can be generated with
+//! `cargo run --features test-certs --bin generate_test_certs`.
+
+// Standard
+use std::fs;
+use std::io::BufReader;
+use std::sync::Arc as StdArc;
+
+// Package
+use common_messages::routing::peer_identity::VerifiedPeers;
+use rustls::client::verify_server_name;
+use rustls::crypto::ring::default_provider;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use rustls::server::{ParsedCertificate, WebPkiClientVerifier};
+use rustls::{ClientConfig, RootCertStore, ServerConfig};
+
+use tokio::io::copy_bidirectional;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+// Local
+use crate::error::RuntimeError;
+use crate::topology::{NetworkTopology, TlsSettings};
+
+
+/// Starts the TLS proxies of a topology with a `<tls>` section
+///
+/// # Returns
+///
+/// * `Result<Option<NetworkTopology>, RuntimeError>` - The topology to hand Hyperion, with every client
+///   connection pointing at its local proxy, or None when TLS is not enabled
+pub async fn secure_links(topology: &NetworkTopology) -> Result<Option<NetworkTopology>, RuntimeError> {
+    let Some(settings) = &topology.tls else { return Ok(None) };
+    let (server_config, client_config) = tls_configs(settings)?;
+    let allowed_clients = allowed_clients(topology)?;
+
+    let listener = TcpListener::bind(&settings.listen_address).await
+        .map_err(|error| RuntimeError::Tls(format!("unable to listen on {}: {}", settings.listen_address, error)))?;
+    log::info!("{} accepts TLS connections on {} from: {}", topology.container_name, settings.listen_address,
+        allowed_clients.iter().map(|name| name.to_str()).collect::<Vec<_>>().join(", "));
+    tokio::spawn(accept_inbound(listener, TlsAcceptor::from(StdArc::new(server_config)), topology.server_address.clone(),
+        StdArc::new(allowed_clients), VerifiedPeers::install()));
+
+    let connector = TlsConnector::from(StdArc::new(client_config));
+    let mut hyperion_topology = topology.clone();
+    hyperion_topology.tls = None;
+    for connection in &mut hyperion_topology.client_connections.connection {
+        let server_name = container_server_name(&connection.name)?;
+        let local_listener = TcpListener::bind("127.0.0.1:0").await
+            .map_err(|error| RuntimeError::Tls(format!("unable to open a local port for {}: {}", connection.name, error)))?;
+        let local_address = local_listener.local_addr()
+            .map_err(|error| RuntimeError::Tls(error.to_string()))?;
+        tokio::spawn(forward_outbound(local_listener, connector.clone(), server_name, connection.address.clone()));
+        log::debug!("Connections to {} go through {} to {}", connection.name, local_address, connection.address);
+        connection.address = local_address.to_string();
+    }
+    Ok(Some(hyperion_topology))
+}
+
+/// Builds the TLS configurations for accepting and making connections with this container's certificate
+///
+/// # Returns
+///
+/// * `Result<(ServerConfig, ClientConfig), RuntimeError>` - Both only trust certificates of the configured authority
+fn tls_configs(settings: &TlsSettings) -> Result<(ServerConfig, ClientConfig), RuntimeError> {
+    let provider = StdArc::new(default_provider());
+    let certificates = load_certificates(&settings.certificate)?;
+    let private_key = load_private_key(&settings.private_key)?;
+    let mut roots = RootCertStore::empty();
+    for ca_certificate in load_certificates(&settings.ca_certificate)? {
+        roots.add(ca_certificate).map_err(|error| RuntimeError::Parse { path: settings.ca_certificate.clone(), message: error.to_string() })?;
+    }
+    let roots = StdArc::new(roots);
+
+    let client_verifier = WebPkiClientVerifier::builder_with_provider(roots.clone(), provider.clone()).build()
+        .map_err(|error| RuntimeError::Tls(error.to_string()))?;
+    let server_config = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|error| RuntimeError::Tls(error.to_string()))?
+        .with_client_cert_verifier(client_verifier)
+        .with_single_cert(certificates.clone(), private_key.clone_key())
+        .map_err(|error| RuntimeError::Tls(format!("{}: {}", settings.certificate, error)))?;
+    let client_config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|error| RuntimeError::Tls(error.to_string()))?
+        .with_root_certificates(roots)
+        .with_client_auth_cert(certificates, private_key)
+        .map_err(|error| RuntimeError::Tls(format!("{}: {}", settings.certificate, error)))?;
+    Ok((server_config, client_config))
+}
+
+/// The containers allowed to connect to this one, never including itself
+fn allowed_clients(topology: &NetworkTopology) -> Result<Vec<ServerName<'static>>, RuntimeError> {
+    let configured = topology.tls.as_ref().map(|settings| settings.allowed_client.clone()).unwrap_or_default();
+    let names = if configured.is_empty() {
+        topology.client_connections.connection.iter().map(|connection| connection.name.clone()).collect()
+    } else {
+        configured
+    };
+    let mut allowed_clients: Vec<ServerName<'static>> = Vec::new();
+    for name in names.iter().filter(|name| **name != topology.container_name) {
+        let server_name = container_server_name(name)?;
+        if !allowed_clients.contains(&server_name) {
+            allowed_clients.push(server_name);
+        }
+    }
+    Ok(allowed_clients)
+}
+
+/// The name a container's certificate is issued for
+fn container_server_name(name: &str) -> Result<ServerName<'static>, RuntimeError> {
+    ServerName::try_from(name.to_string())
+        .map_err(|_| RuntimeError::Tls(format!("container name `{}` can't be checked against a certificate", name)))
+}
+
+/// Forwards authenticated TLS connections from other containers to the Hyperion server
+async fn accept_inbound(listener: TcpListener, acceptor: TlsAcceptor, server_address: String,
+    allowed_clients: StdArc<Vec<ServerName<'static>>>, verified_peers: &'static VerifiedPeers) {
+    loop {
+        let (stream, peer_address) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(error) => {
+                log::warn!("Unable to accept a TLS connection: {}", error);
+                continue;
+            }
+        };
+        let acceptor = acceptor.clone();
+        let server_address = server_address.clone();
+        let allowed_clients = allowed_clients.clone();
+        tokio::spawn(async move {
+            let mut tls_stream = match acceptor.accept(stream).await {
+                Ok(tls_stream) => tls_stream,
+                Err(error) => {
+                    log::debug!("TLS handshake with {} failed: {}", peer_address, error);
+                    return;
+                }
+            };
+            let peer_name = tls_stream.get_ref().1.peer_certificates()
+                .and_then(|certificates| certificates.first())
+                .and_then(|certificate| ParsedCertificate::try_from(certificate).ok()
+                    .and_then(|parsed| allowed_clients.iter().find(|name| verify_server_name(&parsed, name).is_ok()).cloned()));
+            let Some(peer_name) = peer_name else {
+                log::warn!("Rejected the TLS connection from {}: its certificate is not for a container allowed to connect", peer_address);
+                return;
+            };
+            let mut server_stream = match TcpStream::connect(&server_address).await {
+                Ok(server_stream) => server_stream,
+                Err(error) => {
+                    log::warn!("Unable to forward {}'s connection to {}: {}", peer_name.to_str(), server_address, error);
+                    return;
+                }
+            };
+            log::debug!("{} connected over TLS from {}", peer_name.to_str(), peer_address);
+            let _verified_connection = verified_peers.open(&peer_name.to_str());
+            if let Err(error) = copy_bidirectional(&mut tls_stream, &mut server_stream).await {
+                log::debug!("TLS connection from {} closed: {}", peer_name.to_str(), error);
+            }
+        });
+    }
+}
+
+/// Forwards Hyperion's connections to a peer over TLS, checking the peer's certificate against its container name
+async fn forward_outbound(listener: TcpListener, connector: TlsConnector, peer_name: ServerName<'static>, peer_address: String) {
+    loop {
+        let mut local_stream = match listener.accept().await {
+            Ok((local_stream, _)) => local_stream,
+            Err(error) => {
+                log::warn!("Unable to accept a connection for {}: {}", peer_name.to_str(), error);
+                continue;
+            }
+        };
+        let connector = connector.clone();
+        let peer_name = peer_name.clone();
+        let peer_address = peer_address.clone();
+        tokio::spawn(async move {
+            let stream = match TcpStream::connect(&peer_address).await {
+                Ok(stream) => stream,
+                Err(error) => {
+                    log::debug!("Unable to reach {} at {}: {}", peer_name.to_str(), peer_address, error);
+                    return;
+                }
+            };
+            let mut tls_stream = match connector.connect(peer_name.clone(), stream).await {
+                Ok(tls_stream) => tls_stream,
+                Err(error) => {
+                    log::error!("TLS handshake with {} at {} failed: {}", peer_name.to_str(), peer_address, error);
+                    return;
+                }
+            };
+            if let Err(error) = copy_bidirectional(&mut local_stream, &mut tls_stream).await {
+                log::debug!("TLS connection to {} closed: {}", peer_name.to_str(), error);
+            }
+        });
+    }
+}
+
+/// Reads every certificate of a PEM file
+fn load_certificates(path: &str) -> Result<Vec<CertificateDer<'static>>, RuntimeError> {
+    let file = fs::File::open(path).map_err(|source| RuntimeError::Io { path: path.to_string(), source })?;
+    let certificates = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| RuntimeError::Parse { path: path.to_string(), message: error.to_string() })?;
+    if certificates.is_empty() {
+        return Err(RuntimeError::Parse { path: path.to_string(), message: "no certificate found".to_string() });
+    }
+    Ok(certificates)
+}
+
+/// Reads the first private key of a PEM file
+fn load_private_key(path: &str) -> Result<PrivateKeyDer<'static>, RuntimeError> {
+    let file = fs::File::open(path).map_err(|source| RuntimeError::Io { path: path.to_string(), source })?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .map_err(|error| RuntimeError::Parse { path: path.to_string(), message: error.to_string() })?
+        .ok_or_else(|| RuntimeError::Parse { path: path.to_string(), message: "no private key found".to_string() })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::time::{timeout, Duration};
+
+    use super::*;
+    use crate::test_certs::generate_test_certs;
+    use crate::topology::{ClientConnections, Connection};
+
+    /// Certificates for ComponentA, ComponentB and ComponentC issued by one authority, and for ComponentB by another
+    struct TestCerts {
+        directory: PathBuf,
+        other_directory: PathBuf
+    }
+
+    impl TestCerts {
+        fn generate(test: &str) -> TestCerts {
+            let directory = std::env::temp_dir().join(format!("hyperion_tls_{}_{}", test, std::process::id()));
+            let other_directory = directory.join("other");
+            let names = ["ComponentA", "ComponentB", "ComponentC"].map(String::from);
+            generate_test_certs(&directory, &names).unwrap();
+            generate_test_certs(&other_directory, &names[1..2]).unwrap();
+            TestCerts { directory, other_directory }
+        }
+    }
+
+    impl Drop for TestCerts {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.directory);
+        }
+    }
+
+    fn settings(directory: &Path, name: &str) -> TlsSettings {
+        let path = |file: String| directory.join(file).display().to_string();
+        TlsSettings {
+            certificate: path(format!("{}.pem", name)),
+            private_key: path(format!("{}.key", name)),
+            ca_certificate: path("ca.pem".to_string()),
+            listen_address: "127.0.0.1:0".to_string(),
+            allowed_client: Vec::new()
+        }
+    }
+
+    fn topology_of_a(directory: &Path) -> NetworkTopology {
+        NetworkTopology {
+            container_name: "ComponentA".to_string(),
+            server_address: "127.0.0.1:0".to_string(),
+            client_connections: ClientConnections {
+                connection: vec![Connection { name: "ComponentB".to_string(), address: "127.0.0.1:0".to_string() }]
+            },
+            tls: Some(settings(directory, "ComponentA"))
+        }
+    }
+
+    /// Starts ComponentA's inbound proxy in front of a stand-in Hyperion server
+    ///
+    /// # Returns
+    ///
+    /// * `(String, TcpListener, &'static VerifiedPeers)` - The TLS address, the stand-in server and the peers
+    ///   ComponentA has verified
+    async fn start_component_a(directory: &Path) -> (String, TcpListener, &'static VerifiedPeers) {
+        let topology = topology_of_a(directory);
+        let (server_config, _) = tls_configs(topology.tls.as_ref().unwrap()).unwrap();
+        let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let verified_peers: &'static VerifiedPeers = Box::leak(Box::new(VerifiedPeers::new()));
+        tokio::spawn(accept_inbound(listener, TlsAcceptor::from(StdArc::new(server_config)),
+            server.local_addr().unwrap().to_string(), StdArc::new(allowed_clients(&topology).unwrap()), verified_peers));
+        (address, server, verified_peers)
+    }
+
+    /// Connects to ComponentA as the container whose certificate is in `directory`, and sends a line through
+    async fn connect_as(directory: &Path, name: &str, address: &str) -> Result<(), String> {
+        let (_, client_config) = tls_configs(&settings(directory, name)).map_err(|error| error.to_string())?;
+        let stream = TcpStream::connect(address).await.map_err(|error| error.to_string())?;
+        let mut tls_stream = TlsConnector::from(StdArc::new(client_config))
+            .connect(container_server_name("ComponentA").unwrap(), stream).await
+            .map_err(|error| error.to_string())?;
+        tls_stream.write_all(b"hello\n").await.map_err(|error| error.to_string())?;
+        tls_stream.flush().await.map_err(|error| error.to_string())?;
+        Ok(())
+    }
+
+    /// Reads what the stand-in Hyperion server receives from its first connection, if any connection arrives
+    async fn received_by_server(server: &TcpListener) -> Option<Vec<u8>> {
+        let (mut stream, _) = timeout(Duration::from_secs(2), server.accept()).await.ok()?.ok()?;
+        let mut received = vec![0u8; 6];
+        timeout(Duration::from_secs(2), stream.read_exact(&mut received)).await.ok()?.ok()?;
+        Some(received)
+    }
+
+    #[tokio::test]
+    async fn an_allowed_container_is_forwarded_and_verified() {
+        let certs = TestCerts::generate("allowed");
+        let (address, server, verified_peers) = start_component_a(&certs.directory).await;
+
+        connect_as(&certs.directory, "ComponentB", &address).await.unwrap();
+        assert_eq!(received_by_server(&server).await.as_deref(), Some(&b"hello\n"[..]));
+        assert!(verified_peers.is_connected("ComponentB"));
+    }
+
+    #[tokio::test]
+    async fn a_certificate_from_another_authority_is_rejected() {
+        let certs = TestCerts::generate("wrong_ca");
+        let (address, server, verified_peers) = start_component_a(&certs.directory).await;
+
+        // The client may only learn of the rejection once it reads, so check nothing got through instead
+        let _ = connect_as(&certs.other_directory, "ComponentB", &address).await;
+        assert_eq!(received_by_server(&server).await, None);
+        assert!(!verified_peers.is_connected("ComponentB"));
+    }
+
+    #[tokio::test]
+    async fn a_container_not_allowed_to_connect_is_rejected() {
+        let certs = TestCerts::generate("not_allowed");
+        let (address, server, verified_peers) = start_component_a(&certs.directory).await;
+
+        let _ = connect_as(&certs.directory, "ComponentC", &address).await;
+        let _ = connect_as(&certs.directory, "ComponentA", &address).await;
+        assert_eq!(received_by_server(&server).await, None);
+        assert!(!verified_peers.is_connected("ComponentC"));
+        assert!(!verified_peers.is_connected("ComponentA"));
+    }
+
+    #[test]
+    fn allowed_clients_default_to_the_client_connections_and_exclude_this_container() {
+        let mut topology = topology_of_a(Path::new("certs"));
+        let names = |topology: &NetworkTopology| allowed_clients(topology).unwrap().iter()
+            .map(|name| name.to_str().to_string()).collect::<Vec<_>>();
+        assert_eq!(names(&topology), vec!["ComponentB"]);
+
+        topology.tls.as_mut().unwrap().allowed_client = vec!["ComponentA".to_string(), "ComponentC".to_string()];
+        assert_eq!(names(&topology), vec!["ComponentC"]);
+    }
+}
@@ -0,0 +1,292 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Topic based publishing on top of Hyperion's point-to-point connections.
+//!
+//! A component publishes a message to a topic rather than naming its targets. Containers declare the
+//! topics they subscribe to in their configuration and announce them with a [`Subscribe`] message when
+//! they start, so the publisher learns its subscribers at runtime.
+//!
+//! This does not make subscribers independent of the publisher's topology. Hyperion only sends to the client
+//! connections a container was created with and can't open a connection to an address learned later, so a
+//! subscriber is only reached if it is one of the publisher's client connections: listed in its
+//! network_topology.xml, or discovered before its container was created when [`crate::discovery`] is enabled.
+//! Any other subscriber's announcement is recorded, and publishing to it is reported once and skipped.
+
+// Standard
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc as StdArc, Mutex};
+
+// Package
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::messages::subscribe::Subscribe;
+use common_messages::routing::container_id::ContainerId;
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+use serde::Deserialize;
+
+use async_trait::async_trait;
+
+// Local
+use crate::router::{HandlerResult, Handles};
+use crate::topology::NetworkTopology;
+
+
+/// The `<subscriptions>` section of a component's configuration.xml
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SubscriptionsConfig {
+    #[serde(default)]
+    pub topic: Vec<String>
+}
+
+/// The topics this container subscribes to and the subscribers other containers have announced
+#[derive(Debug, Clone)]
+pub struct TopicRegistry {
+    inner: StdArc<Mutex<RegistryState>>
+}
+
+#[derive(Debug)]
+struct RegistryState {
+    container_name: String,
+    own_topics: Vec<String>,
+    /// Topics each other container has announced
+    subscriptions: HashMap<String, BTreeSet<String>>,
+    /// Subscribers already reported as unreachable
+    unreachable_reported: HashSet<String>
+}
+
+impl TopicRegistry {
+    pub fn new(container_name: &str, config: &SubscriptionsConfig) -> Self {
+        Self {
+            inner: StdArc::new(Mutex::new(RegistryState {
+                container_name: container_name.to_string(),
+                own_topics: config.topic.clone(),
+                subscriptions: HashMap::new(),
+                unreachable_reported: HashSet::new()
+            }))
+        }
+    }
+
+    /// Announces this container's subscriptions to every container it connects to
+    pub fn announcement(&self) -> Option<ClientBrokerMessage<ContainerMessage>> {
+        self.announcement_to(NetworkTopology::current()?)
+    }
+
+    /// Announces this container's subscriptions to the client connections of `topology`
+    fn announcement_to(&self, topology: &NetworkTopology) -> Option<ClientBrokerMessage<ContainerMessage>> {
+        let state = self.inner.lock().expect("topic registry lock poisoned");
+        let targets: Vec<&str> = topology.client_connections.connection.iter().map(|connection| connection.name.as_str()).collect();
+        if targets.is_empty() { return None; }
+        let subscribe = Subscribe::new(state.container_name.clone(), state.own_topics.clone());
+        Some(ClientBrokerMessage::new(targets, ContainerMessage::Subscribe(subscribe)))
+    }
+
+    /// Replaces this container's subscriptions, e.g. after a configuration reload, and announces them
+    pub fn resubscribe(&self, config: &SubscriptionsConfig) -> Option<ClientBrokerMessage<ContainerMessage>> {
+        {
+            let mut state = self.inner.lock().expect("topic registry lock poisoned");
+            if state.own_topics == config.topic { return None; }
+            state.own_topics = config.topic.clone();
+        }
+        self.announcement()
+    }
+
+    /// Records another container's subscriptions and answers an announcement that isn't a reply, if the
+    /// announcing container is one this container connects to. Announcements from containers that aren't a
+    /// [`ContainerId`] are ignored, so only known containers are published to.
+    pub fn record(&self, subscribe: &Subscribe) -> Option<ClientBrokerMessage<ContainerMessage>> {
+        if let Err(error) = ContainerId::of_container(&subscribe.container) {
+            log::warn!("Ignored the subscriptions of {}: {}", subscribe.container, error);
+            return None;
+        }
+        let mut state = self.inner.lock().expect("topic registry lock poisoned");
+        log::debug!("{} subscribes to {:?}", subscribe.container, subscribe.topics);
+        state.subscriptions.insert(subscribe.container.clone(), subscribe.topics.iter().cloned().collect());
+        let reachable = NetworkTopology::current().is_none_or(|topology| topology.connection(&subscribe.container).is_some());
+        if subscribe.is_reply || !reachable { return None; }
+        let reply = Subscribe::reply(state.container_name.clone(), state.own_topics.clone());
+        Some(ClientBrokerMessage::new(vec![subscribe.container.as_str()], ContainerMessage::Subscribe(reply)))
+    }
+
+    /// Containers subscribed to a topic, in name order
+    pub fn subscribers(&self, topic: &str) -> Vec<String> {
+        let state = self.inner.lock().expect("topic registry lock poisoned");
+        let mut subscribers: Vec<String> = state.subscriptions.iter()
+            .filter(|(_, topics)| topics.contains(topic))
+            .map(|(container, _)| container.clone())
+            .collect();
+        subscribers.sort();
+        subscribers
+    }
+
+    /// Addresses a message to every subscriber of the topic this container can reach
+    ///
+    /// # Returns
+    ///
+    /// * `Option<ClientBrokerMessage<ContainerMessage>>` - None when the topic has no reachable subscribers
+    pub fn publish(&self, topic: &str, message: ContainerMessage) -> Option<ClientBrokerMessage<ContainerMessage>> {
+        self.publish_within(NetworkTopology::current(), topic, message)
+    }
+
+    /// Addresses a message to every subscriber of the topic among the client connections of `topology`, or to
+    /// every subscriber without one
+    fn publish_within(&self, topology: Option<&NetworkTopology>, topic: &str, message: ContainerMessage)
+        -> Option<ClientBrokerMessage<ContainerMessage>> {
+        let subscribers = self.subscribers(topic);
+        let (reachable, unreachable): (Vec<String>, Vec<String>) = subscribers.into_iter()
+            .partition(|subscriber| topology.is_none_or(|topology| topology.connection(subscriber).is_some()));
+
+        let mut state = self.inner.lock().expect("topic registry lock poisoned");
+        for subscriber in unreachable {
+            if state.unreachable_reported.insert(subscriber.clone()) {
+                log::warn!("{} subscribes to {} but is not one of {}'s client connections, so it won't receive \
+                    the topic's messages until it is added to the network topology or discovered before a restart",
+                    subscriber, topic, state.container_name);
+            }
+        }
+        if reachable.is_empty() {
+            log::debug!("{} published a {} message to {}, which has no subscribers", state.container_name,
+                message.kind(), topic);
+            return None;
+        }
+        Some(ClientBrokerMessage::new(reachable.iter().map(String::as_str).collect(), message))
+    }
+}
+
+/// Records the subscriptions other containers announce
+pub struct SubscribeHandler {
+    topics: TopicRegistry
+}
+
+impl SubscribeHandler {
+    pub fn new(topics: &TopicRegistry) -> Self {
+        Self { topics: topics.clone() }
+    }
+}
+
+#[async_trait]
+impl<C: Sync> Handles<Subscribe, C> for SubscribeHandler {
+    async fn handle(&mut self, subscribe: Subscribe, _context: &C) -> HandlerResult {
+        self.topics.record(&subscribe)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_messages::messages::example_message::ExampleMessage;
+
+    use crate::topology::{ClientConnections, Connection};
+
+    fn registry(topics: &[&str]) -> TopicRegistry {
+        TopicRegistry::new("ComponentB", &SubscriptionsConfig { topic: topics.iter().map(|topic| topic.to_string()).collect() })
+    }
+
+    fn announce(registry: &TopicRegistry, container: &str, topics: &[&str]) -> Option<ClientBrokerMessage<ContainerMessage>> {
+        registry.record(&Subscribe::new(container.to_string(), topics.iter().map(|topic| topic.to_string()).collect()))
+    }
+
+    fn example() -> ContainerMessage {
+        ContainerMessage::ExampleMessage(ExampleMessage::new("x".to_string(), 1))
+    }
+
+    fn topology(connections: &[&str]) -> NetworkTopology {
+        NetworkTopology {
+            container_name: "ComponentB".to_string(),
+            server_address: "127.0.0.1:8001".to_string(),
+            client_connections: ClientConnections {
+                connection: connections.iter()
+                    .map(|name| Connection { name: name.to_string(), address: "127.0.0.1:8000".to_string() })
+                    .collect()
+            },
+            tls: None
+        }
+    }
+
+    #[test]
+    fn messages_are_only_published_to_subscribers() {
+        let registry = registry(&[]);
+        announce(&registry, "ComponentA", &["examples"]);
+        announce(&registry, "ComponentB-1", &["other"]);
+        let published = registry.publish("examples", example()).unwrap();
+        assert_eq!(published.client_name_list, vec!["ComponentA".to_string()]);
+        assert!(registry.publish("nobody", example()).is_none());
+    }
+
+    #[test]
+    fn unreachable_subscribers_are_skipped() {
+        let registry = registry(&[]);
+        announce(&registry, "ComponentA", &["examples"]);
+        announce(&registry, "ComponentB-1", &["examples"]);
+        let published = registry.publish_within(Some(&topology(&["ComponentA"])), "examples", example()).unwrap();
+        assert_eq!(published.client_name_list, vec!["ComponentA".to_string()]);
+        assert!(registry.publish_within(Some(&topology(&[])), "examples", example()).is_none());
+    }
+
+    #[test]
+    fn unknown_containers_are_not_subscribed() {
+        let registry = registry(&["examples"]);
+        assert!(announce(&registry, "ComponentC", &["examples"]).is_none());
+        assert!(registry.subscribers("examples").is_empty());
+        assert!(registry.publish("examples", example()).is_none());
+    }
+
+    #[test]
+    fn subscribing_twice_keeps_one_subscription() {
+        let registry = registry(&[]);
+        announce(&registry, "ComponentA", &["examples"]);
+        announce(&registry, "ComponentA", &["examples"]);
+        assert_eq!(registry.subscribers("examples"), vec!["ComponentA".to_string()]);
+        assert_eq!(registry.publish("examples", example()).unwrap().client_name_list, vec!["ComponentA".to_string()]);
+    }
+
+    #[test]
+    fn announcing_fewer_topics_unsubscribes() {
+        let registry = registry(&[]);
+        announce(&registry, "ComponentA", &["examples", "other"]);
+        announce(&registry, "ComponentA", &["other"]);
+        assert!(registry.subscribers("examples").is_empty());
+        assert!(registry.publish("examples", example()).is_none());
+        assert_eq!(registry.subscribers("other"), vec!["ComponentA".to_string()]);
+    }
+
+    #[test]
+    fn a_restarted_peer_is_answered_again() {
+        let registry = registry(&["examples"]);
+        for _ in 0..2 {
+            let answer = announce(&registry, "ComponentA", &["examples"]).unwrap();
+            assert_eq!(answer.client_name_list, vec!["ComponentA".to_string()]);
+            let ContainerMessage::Subscribe(reply) = answer.message else {
+                panic!("expected a Subscribe reply");
+            };
+            assert!(reply.is_reply);
+            assert_eq!(reply.container, "ComponentB");
+            assert_eq!(reply.topics, vec!["examples".to_string()]);
+        }
+        // Answers aren't answered, so announcements don't bounce back and forth
+        assert!(registry.record(&Subscribe::reply("ComponentA".to_string(), Vec::new())).is_none());
+    }
+
+    #[test]
+    fn resubscribing_announces_the_new_topics() {
+        let registry = registry(&["examples"]);
+        let announcement = registry.announcement_to(&topology(&["ComponentA", "ComponentB-1"])).unwrap();
+        assert_eq!(announcement.client_name_list, vec!["ComponentA".to_string(), "ComponentB-1".to_string()]);
+        assert!(registry.announcement_to(&topology(&[])).is_none());
+
+        // Unchanged subscriptions aren't announced again
+        assert!(registry.resubscribe(&SubscriptionsConfig { topic: vec!["examples".to_string()] }).is_none());
+        registry.resubscribe(&SubscriptionsConfig { topic: vec!["other".to_string()] });
+        let ContainerMessage::Subscribe(subscribe) = registry.announcement_to(&topology(&["ComponentA"])).unwrap().message else {
+            panic!("expected a Subscribe announcement");
+        };
+        assert_eq!(subscribe.topics, vec!["other".to_string()]);
+    }
+}
@@ -0,0 +1,184 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+// Standard
+use std::fs;
+use std::sync::OnceLock;
+
+// Package
+use common_messages::routing::container_id::ContainerId;
+use serde::{Deserialize, Serialize};
+
+// Local
+use crate::config_loader::load_xml;
+use crate::error::RuntimeError;
+use crate::template::RenderedDir;
+
+
+/// Root element of network_topology.xml
+const ROOT_ELEMENT: &str = "network_config";
+
+/// Topology of the running container, set by the runtime before the container is created
+static CURRENT_TOPOLOGY: OnceLock<NetworkTopology> = OnceLock::new();
+
+/// Contents of a container's network_topology.xml
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkTopology {
+    pub container_name: String,
+    pub server_address: String,
+    #[serde(default)]
+    pub client_connections: ClientConnections,
+    /// Secures the links to and from this container with TLS, see [`crate::tls`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsSettings>
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClientConnections {
+    #[serde(default)]
+    pub connection: Vec<Connection>
+}
+
+/// A container this container connects to as a client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Connection {
+    pub name: String,
+    pub address: String
+}
+
+/// The `<tls>` section of a network_topology.xml
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsSettings {
+    /// PEM certificate chain of this container, issued for its container name
+    pub certificate: String,
+    /// PEM private key of the certificate
+    pub private_key: String,
+    /// PEM certificate of the authority that issued every container's certificate
+    pub ca_certificate: String,
+    /// Address other containers connect to, whose TLS connections are forwarded to `server_address`
+    pub listen_address: String,
+    /// Containers allowed to connect to `listen_address`, one `<allowed_client>` each. Defaults to the containers
+    /// of the client connections, as links are usually two-way.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_client: Vec<String>
+}
+
+impl NetworkTopology {
+    /// Reads a network_topology.xml file
+    pub fn load(path: &str) -> Result<NetworkTopology, RuntimeError> {
+        load_xml(path)
+    }
+
+    /// Makes this the topology of the running container, see [`NetworkTopology::current`]
+    pub fn set_current(self) {
+        if CURRENT_TOPOLOGY.set(self).is_err() {
+            log::warn!("The network topology of this container has already been set");
+        }
+    }
+
+    /// Topology the running container was started with, for components that need their peers' addresses
+    pub fn current() -> Option<&'static NetworkTopology> {
+        CURRENT_TOPOLOGY.get()
+    }
+
+    /// Address other containers reach this container at, i.e. the TLS address when TLS is enabled
+    pub fn public_address(&self) -> &str {
+        self.tls.as_ref().map_or(&self.server_address, |tls| &tls.listen_address)
+    }
+
+    /// The client connection with the given container name
+    pub fn connection(&self, name: &str) -> Option<&Connection> {
+        self.client_connections.connection.iter().find(|connection| connection.name == name)
+    }
+
+    /// Adds a client connection, replacing the address of an existing one with the same name
+    pub fn set_connection(&mut self, name: &str, address: &str) {
+        match self.client_connections.connection.iter_mut().find(|connection| connection.name == name) {
+            Some(connection) => connection.address = address.to_string(),
+            None => self.client_connections.connection.push(Connection { name: name.to_string(), address: address.to_string() })
+        }
+    }
+
+    /// Writes the topology to this process' directory of generated config files, e.g. once peers have been discovered
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, RuntimeError>` - Path of the written network_topology.xml
+    pub fn write_generated(&self, rendered_dir: &RenderedDir) -> Result<String, RuntimeError> {
+        // Rendered copies carry a hash in their name, so this can't replace one of them
+        let path = rendered_dir.path().join("network_topology.xml");
+        let path_text = path.display().to_string();
+        let mut contents = String::new();
+        let mut serializer = quick_xml::se::Serializer::with_root(&mut contents, Some(ROOT_ELEMENT))
+            .map_err(|error| RuntimeError::Serialize { path: path_text.clone(), message: error.to_string() })?;
+        serializer.indent(' ', 4);
+        self.serialize(serializer)
+            .map_err(|error| RuntimeError::Serialize { path: path_text.clone(), message: error.to_string() })?;
+
+        fs::write(&path, contents)
+            .map_err(|source| RuntimeError::Write { path: path_text.clone(), source })?;
+        Ok(path_text)
+    }
+
+    /// Checks that every routing target is one of the client connections
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - Containers a component sends messages to
+    /// * `path` - Path the topology was read from, named in errors
+    pub fn check_routing_targets(&self, targets: &[ContainerId], path: &str) -> Result<(), RuntimeError> {
+        match targets.iter().find(|target| self.connection(target.as_str()).is_none()) {
+            Some(target) => Err(RuntimeError::UnknownRoutingTarget { target: target.to_string(), path: path.to_string() }),
+            None => Ok(())
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topology(connections: &[&str]) -> NetworkTopology {
+        let mut topology = NetworkTopology {
+            container_name: "ComponentA".to_string(),
+            server_address: "127.0.0.1:50501".to_string(),
+            client_connections: ClientConnections::default(),
+            tls: None
+        };
+        for name in connections {
+            topology.set_connection(name, "127.0.0.1:50502");
+        }
+        topology
+    }
+
+    #[test]
+    fn routing_targets_must_be_client_connections() {
+        assert!(topology(&["ComponentB"]).check_routing_targets(&[ContainerId::ComponentB], "network_topology.xml").is_ok());
+        assert!(topology(&[]).check_routing_targets(&[], "network_topology.xml").is_ok());
+
+        match topology(&["ComponentB-1"]).check_routing_targets(&[ContainerId::ComponentB], "network_topology.xml") {
+            Err(RuntimeError::UnknownRoutingTarget { target, path }) => {
+                assert_eq!(target, "ComponentB");
+                assert_eq!(path, "network_topology.xml");
+            }
+            other => panic!("expected an UnknownRoutingTarget error, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn setting_a_connection_replaces_its_address() {
+        let mut topology = topology(&["ComponentB"]);
+        topology.set_connection("ComponentB", "10.0.0.2:50502");
+        assert_eq!(topology.client_connections.connection.len(), 1);
+        assert_eq!(topology.connection("ComponentB").map(|connection| connection.address.as_str()), Some("10.0.0.2:50502"));
+    }
+}
@@ -0,0 +1,366 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+//! Distributed tracing of messages as they hop between containers.
+//!
+//! Reads the `<tracing>` section of a component's configuration.xml. When enabled, components handle every message
+//! in a span and send every message in a child span, wrapped in a [`Traced`] message carrying that span's W3C trace
+//! context. The receiving container handles the message in a span of the same trace, so the path of one request
+//! through the network can be followed from container to container.
+//!
+//! Spans are recorded with `tracing` and exported through OpenTelemetry, either as one JSON object per line to a
+//! file or to an OTLP collector (e.g. the OpenTelemetry Collector or Jaeger) over HTTP.
+
+// Standard
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Package
+use common_messages::messages::container_message::ContainerMessage;
+use common_messages::messages::traced::Traced;
+use hyperion_framework::messages::client_broker_message::ClientBrokerMessage;
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::trace::{TraceContextExt, TracerProvider};
+use opentelemetry_otlp::{Protocol, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::error::{OTelSdkError, OTelSdkResult};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{SdkTracerProvider, SpanData, SpanExporter};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+// Local
+use crate::config_loader::load_xml;
+use crate::error::RuntimeError;
+
+
+/// Directory span files are written to when the configuration doesn't say
+pub const DEFAULT_TRACE_DIRECTORY: &str = "traces";
+/// OTLP/HTTP traces endpoint of a collector running locally with its default ports
+pub const DEFAULT_OTLP_ENDPOINT: &str = "http://127.0.0.1:4318/v1/traces";
+/// Key of the W3C trace context header, as carried by a Traced message
+const TRACEPARENT: &str = "traceparent";
+
+/// Where finished spans are exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum TraceExporter {
+    /// Append one JSON object per span to `<directory>/<container name>.jsonl`
+    #[default]
+    File,
+    /// Send them to an OTLP collector over HTTP, as JSON
+    Otlp
+}
+
+/// The `<tracing>` section of a component's configuration.xml
+#[derive(Debug, Clone, Deserialize)]
+pub struct TracingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub exporter: TraceExporter,
+    /// Directory of the span files written by the File exporter
+    #[serde(default = "default_directory")]
+    pub directory: String,
+    /// Traces endpoint of the collector used by the Otlp exporter
+    #[serde(default = "default_otlp_endpoint")]
+    pub otlp_endpoint: String
+}
+
+impl Default for TracingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            exporter: TraceExporter::default(),
+            directory: default_directory(),
+            otlp_endpoint: default_otlp_endpoint()
+        }
+    }
+}
+
+fn default_directory() -> String {
+    DEFAULT_TRACE_DIRECTORY.to_string()
+}
+
+fn default_otlp_endpoint() -> String {
+    DEFAULT_OTLP_ENDPOINT.to_string()
+}
+
+/// The parts of configuration.xml read by the runtime rather than the component
+#[derive(Debug, Default, Deserialize)]
+struct TracingSettings {
+    #[serde(default)]
+    tracing: TracingConfig
+}
+
+/// Exports the spans of this process, set once tracing has started
+static PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+/// Starts recording and exporting spans if tracing is enabled in the configuration
+///
+/// Spans are exported in batches from a background thread, see [`flush_traces`] for the ones still buffered when
+/// the process exits. Changes to `<tracing>` need a restart.
+///
+/// # Arguments
+///
+/// * `config_path` - The component's configuration.xml
+/// * `container_name` - Service name the spans are exported under
+///
+/// # Returns
+///
+/// * `Result<bool, RuntimeError>` - Whether tracing is enabled
+pub fn start_tracing(config_path: &str, container_name: &str) -> Result<bool, RuntimeError> {
+    let config = load_xml::<TracingSettings>(config_path)?.tracing;
+    if !config.enabled {
+        return Ok(false);
+    }
+
+    let builder = SdkTracerProvider::builder()
+        .with_resource(Resource::builder().with_service_name(container_name.to_string()).build());
+    let (builder, destination) = match config.exporter {
+        TraceExporter::File => {
+            let exporter = JsonFileExporter::create(Path::new(&config.directory), container_name)?;
+            let destination = exporter.path.display().to_string();
+            (builder.with_batch_exporter(exporter), destination)
+        }
+        TraceExporter::Otlp => {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_http()
+                .with_protocol(Protocol::HttpJson)
+                .with_endpoint(config.otlp_endpoint.clone())
+                .build()
+                .map_err(|error| RuntimeError::Tracing(format!("{}: {}", config.otlp_endpoint, error)))?;
+            (builder.with_batch_exporter(exporter), config.otlp_endpoint.clone())
+        }
+    };
+    let provider = builder.build();
+
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("hyperion_examples_runtime")));
+    if tracing::subscriber::set_global_default(subscriber).is_err() || PROVIDER.set(provider).is_err() {
+        log::warn!("Tracing was already set up, {} is not applied again", config_path);
+        return Ok(true);
+    }
+    log::info!("{} exports its message traces to {}", container_name, destination);
+    Ok(true)
+}
+
+/// Whether spans are being recorded, i.e. tracing is enabled and has started
+pub fn is_enabled() -> bool {
+    PROVIDER.get().is_some()
+}
+
+/// Trace id of the span the caller is in, e.g. to find the spans of a log record
+///
+/// None outside of a recorded span, e.g. while tracing is disabled.
+pub fn current_trace_id() -> Option<String> {
+    let context = Span::current().context();
+    let span_context = context.span().span_context().clone();
+    span_context.is_valid().then(|| span_context.trace_id().to_string())
+}
+
+/// Exports the spans still buffered, before the process exits
+pub fn flush_traces() {
+    let Some(provider) = PROVIDER.get() else { return };
+    if let Err(error) = provider.shutdown() {
+        log::warn!("Not every span could be exported: {}", error);
+    }
+}
+
+/// Takes the trace context off a received message and opens the span it is handled in
+///
+/// The span continues the sender's trace, or starts a new one for a message that carries none (e.g. one from the
+/// console). With tracing disabled the message is only unwrapped and the span is disabled.
+///
+/// # Arguments
+///
+/// * `container_name` - Name of the container handling the message
+/// * `message` - The message as received
+///
+/// # Returns
+///
+/// * `(ContainerMessage, Span)` - The message without its trace context and the span to handle it in
+pub fn receive(container_name: &str, message: ContainerMessage) -> (ContainerMessage, Span) {
+    let (message, traceparent) = match message {
+        ContainerMessage::Traced(traced) => (*traced.payload, Some(traced.traceparent)),
+        message => (message, None)
+    };
+    // Disabled unless spans are being recorded
+    let span = tracing::info_span!("handle_message", container = container_name, kind = payload_kind(&message));
+    if span.is_disabled() {
+        return (message, span);
+    }
+    if let Some(traceparent) = traceparent {
+        let carrier = HashMap::from([(TRACEPARENT.to_string(), traceparent)]);
+        if let Err(error) = span.set_parent(TraceContextPropagator::new().extract(&carrier)) {
+            log::debug!("{} could not continue the trace of a {} message: {:?}", container_name, message.kind(), error);
+        }
+    }
+    (message, span)
+}
+
+/// Opens the span a message is sent in, as a child of the current span, and wraps the message in its trace context
+///
+/// Container directives are left unwrapped so the receiving Hyperion container still recognises them. With tracing
+/// disabled the message is returned as it is and the span is disabled.
+///
+/// # Arguments
+///
+/// * `container_name` - Name of the sending container
+/// * `broker_message` - The message about to be sent
+///
+/// # Returns
+///
+/// * `(ClientBrokerMessage<ContainerMessage>, Span)` - The message to send and the span to send it in
+pub fn send(container_name: &str, broker_message: ClientBrokerMessage<ContainerMessage>)
+    -> (ClientBrokerMessage<ContainerMessage>, Span) {
+    if matches!(broker_message.message, ContainerMessage::ContainerDirectiveMsg(_)) {
+        return (broker_message, Span::none());
+    }
+
+    let span = tracing::info_span!("send_message", container = container_name,
+        kind = payload_kind(&broker_message.message), targets = broker_message.client_name_list.join(",").as_str());
+    if span.is_disabled() {
+        return (broker_message, span);
+    }
+    let mut carrier = HashMap::new();
+    TraceContextPropagator::new().inject_context(&span.context(), &mut carrier);
+    let broker_message = match carrier.remove(TRACEPARENT) {
+        Some(traceparent) => ClientBrokerMessage {
+            client_name_list: broker_message.client_name_list,
+            message: ContainerMessage::Traced(Traced::new(traceparent, broker_message.message))
+        },
+        None => broker_message
+    };
+    (broker_message, span)
+}
+
+/// Kind of the message a span is about, looking through reliable delivery
+fn payload_kind(message: &ContainerMessage) -> &str {
+    match message {
+        ContainerMessage::Reliable(reliable) => reliable.payload.kind(),
+        message => message.kind()
+    }
+}
+
+/// Appends finished spans to a file as JSON lines
+#[derive(Debug)]
+struct JsonFileExporter {
+    path: PathBuf,
+    file: Mutex<File>,
+    service_name: String
+}
+
+impl JsonFileExporter {
+    /// Opens `<directory>/<container name>.jsonl` for appending, creating the directory if needed
+    fn create(directory: &Path, container_name: &str) -> Result<Self, RuntimeError> {
+        let path = directory.join(format!("{}.jsonl", container_name));
+        fs::create_dir_all(directory)
+            .map_err(|source| RuntimeError::Write { path: directory.display().to_string(), source })?;
+        let file = OpenOptions::new().create(true).append(true).open(&path)
+            .map_err(|source| RuntimeError::Write { path: path.display().to_string(), source })?;
+        Ok(Self { path, file: Mutex::new(file), service_name: container_name.to_string() })
+    }
+
+    /// One span as a single line of JSON
+    fn span_line(&self, span: &SpanData) -> String {
+        let attributes: serde_json::Map<String, serde_json::Value> = span.attributes.iter()
+            .map(|attribute| (attribute.key.to_string(), json!(attribute.value.to_string())))
+            .collect();
+        let parent_span_id = if span.parent_span_id == opentelemetry::trace::SpanId::INVALID {
+            None
+        } else {
+            Some(span.parent_span_id.to_string())
+        };
+        json!({
+            "service": self.service_name,
+            "trace_id": span.span_context.trace_id().to_string(),
+            "span_id": span.span_context.span_id().to_string(),
+            "parent_span_id": parent_span_id,
+            "name": span.name,
+            "start_unix_nano": unix_nanos(span.start_time),
+            "end_unix_nano": unix_nanos(span.end_time),
+            "attributes": attributes
+        }).to_string()
+    }
+}
+
+impl SpanExporter for JsonFileExporter {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        let lines: String = batch.iter().map(|span| self.span_line(span) + "\n").collect();
+        let mut file = self.file.lock().map_err(|_| OTelSdkError::InternalFailure("span file lock poisoned".to_string()))?;
+        file.write_all(lines.as_bytes())
+            .and_then(|_| file.flush())
+            .map_err(|error| OTelSdkError::InternalFailure(format!("could not write {}: {}", self.path.display(), error)))
+    }
+}
+
+/// Nanoseconds since the Unix epoch, 0 for times before it
+fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH).map(|duration| duration.as_nanos()).unwrap_or_default()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_messages::messages::example_message::ExampleMessage;
+
+    #[test]
+    fn nothing_is_wrapped_while_spans_are_not_recorded() {
+        let message = ClientBrokerMessage::new(vec!["ComponentB"], ContainerMessage::ExampleMessage(ExampleMessage::default()));
+        let (sent, _span) = send("ComponentA", message);
+        assert!(matches!(sent.message, ContainerMessage::ExampleMessage(_)));
+    }
+
+    #[test]
+    fn a_received_message_continues_the_senders_trace() {
+        let directory = std::env::temp_dir().join(format!("hyperion_traces_{}", std::process::id()));
+        let exporter = JsonFileExporter::create(&directory, "ComponentA").unwrap();
+        let path = exporter.path.clone();
+        let provider = SdkTracerProvider::builder().with_simple_exporter(exporter).build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("trace_tests")));
+
+        let traceparent = tracing::subscriber::with_default(subscriber, || {
+            let message = ClientBrokerMessage::new(vec!["ComponentB"],
+                ContainerMessage::ExampleMessage(ExampleMessage::default()));
+            let (sent, send_span) = send("ComponentA", message);
+            drop(send_span);
+            let ContainerMessage::Traced(traced) = &sent.message else {
+                panic!("expected the sent message to carry its trace context");
+            };
+            let traceparent = traced.traceparent.clone();
+
+            let (received, handle_span) = receive("ComponentB", sent.message);
+            assert!(matches!(received, ContainerMessage::ExampleMessage(_)));
+            drop(handle_span);
+            traceparent
+        });
+        provider.shutdown().unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let spans: Vec<serde_json::Value> = contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        let _ = fs::remove_dir_all(&directory);
+        let span_named = |name: &str| spans.iter().find(|span| span["name"] == name).unwrap().clone();
+        let (send_span, handle_span) = (span_named("send_message"), span_named("handle_message"));
+        let trace_id = traceparent.split('-').nth(1).unwrap();
+        assert_eq!(send_span["trace_id"], trace_id);
+        assert_eq!(handle_span["trace_id"], trace_id);
+        assert_eq!(handle_span["parent_span_id"], send_span["span_id"]);
+        assert_eq!(handle_span["attributes"]["container"], "ComponentB");
+    }
+}
@@ -0,0 +1,227 @@
+// -------------------------------------------------------------------------------------------------
+// Hyperion Framework Example 1
+// https://github.com/Bazzz-1/hyperion-framework-examples
+// https://github.com/Bazzz-1/hyperion-framework
+//
+// A lightweight component-based TCP framework for building service-oriented Rust applications with
+// CLI control, async messaging, and lifecycle management.
+//
+// Example written by Robert Hannah 2025
+// -------------------------------------------------------------------------------------------------
+
+//! Derive macro generating the boilerplate of a Hyperion container message enum.
+//!
+//! ```ignore
+//! #[derive(Debug, Clone, HyperionMessage)]
+//! pub enum ContainerMessage {
+//!     #[hyperion(directive)]
+//!     ContainerDirectiveMsg       (ContainerDirective),
+//!     ExampleMessage              (ExampleMessage),
+//!     #[hyperion(unknown)]
+//!     UnknownMessage              (UnknownMessage),
+//! }
+//! ```
+//!
+//! Every variant wraps exactly one message type. For the enum this generates:
+//!
+//! * `HyperionContainerDirectiveMessage`, returning the variant marked `#[hyperion(directive)]`
+//! * `From<Message>` for the enum and `TryFrom<Enum>` back to each message type
+//! * `common_messages::messages::message_variant::MessageVariant` for each message type
+//! * `kind()`, the message type name written to the wire, and `KINDS`, every known type name
+//! * `decode_payload()` and `serialize_payload()`, used by the enum's wire format
+//!
+//! The optional `#[hyperion(unknown)]` variant holds messages that could not be decoded. Its type must
+//! have `kind: String` and `payload: serde_json::Value` fields; it is excluded from `KINDS` and decoding.
+//!
+//! Variants marked `#[hyperion(local)]` are messages a container only accepts from within itself, e.g. from
+//! its console. They are decoded like any other message, and `is_local_only()` tells them apart.
+//!
+//! A variant marked `#[hyperion(signed)]` wraps a message signed for sending. Its type must have a
+//! `container_directive()` method returning the signed container directive, if any, which the directive accessor
+//! returns as well.
+
+// Package
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, Type};
+
+
+/// Role of a variant, given by its `#[hyperion(..)]` attribute
+#[derive(PartialEq)]
+enum VariantRole {
+    Message,
+    Directive,
+    Unknown,
+    Local,
+    Signed
+}
+
+/// A single-field tuple variant of the message enum
+struct MessageVariant {
+    ident: Ident,
+    ty: Type,
+    role: VariantRole
+}
+
+#[proc_macro_derive(HyperionMessage, attributes(hyperion))]
+pub fn derive_hyperion_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let enum_ident = &input.ident;
+    let variants = parse_variants(&input)?;
+
+    let mut directives = variants.iter().filter(|variant| variant.role == VariantRole::Directive);
+    let directive = directives.next().ok_or_else(|| syn::Error::new_spanned(enum_ident,
+        "HyperionMessage needs one variant marked #[hyperion(directive)] holding the ContainerDirective"))?;
+    if let Some(duplicate) = directives.next() {
+        return Err(syn::Error::new_spanned(&duplicate.ident, "only one variant can be marked #[hyperion(directive)]"));
+    }
+    let unknowns: Vec<&MessageVariant> = variants.iter().filter(|variant| variant.role == VariantRole::Unknown).collect();
+    if let Some(duplicate) = unknowns.get(1) {
+        return Err(syn::Error::new_spanned(&duplicate.ident, "only one variant can be marked #[hyperion(unknown)]"));
+    }
+    let known: Vec<&MessageVariant> = variants.iter().filter(|variant| variant.role != VariantRole::Unknown).collect();
+
+    let known_idents: Vec<&Ident> = known.iter().map(|variant| &variant.ident).collect();
+    let known_kinds: Vec<String> = known_idents.iter().map(|ident| ident.to_string()).collect();
+    let unknown_idents: Vec<&Ident> = unknowns.iter().map(|variant| &variant.ident).collect();
+    let local_idents: Vec<&Ident> = variants.iter()
+        .filter(|variant| variant.role == VariantRole::Local)
+        .map(|variant| &variant.ident)
+        .collect();
+    let signed_idents: Vec<&Ident> = variants.iter()
+        .filter(|variant| variant.role == VariantRole::Signed)
+        .map(|variant| &variant.ident)
+        .collect();
+    let directive_ident = &directive.ident;
+    let directive_ty = &directive.ty;
+
+    let conversions = variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let ty = &variant.ty;
+        let kind = ident.to_string();
+        let message_variant = (variant.role != VariantRole::Unknown).then(|| quote! {
+            impl ::common_messages::messages::message_variant::MessageVariant<#enum_ident> for #ty {
+                const KIND: &'static str = #kind;
+            }
+        });
+        quote! {
+            #message_variant
+
+            impl ::core::convert::From<#ty> for #enum_ident {
+                fn from(message: #ty) -> Self {
+                    #enum_ident::#ident(message)
+                }
+            }
+
+            impl ::core::convert::TryFrom<#enum_ident> for #ty {
+                type Error = #enum_ident;
+
+                fn try_from(message: #enum_ident) -> ::core::result::Result<Self, Self::Error> {
+                    match message {
+                        #enum_ident::#ident(inner) => ::core::result::Result::Ok(inner),
+                        other => ::core::result::Result::Err(other)
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl ::hyperion_framework::containerisation::traits::HyperionContainerDirectiveMessage for #enum_ident {
+            fn get_container_directive_message(&self) -> ::core::option::Option<&#directive_ty> {
+                #[allow(unreachable_patterns)]
+                match self {
+                    #enum_ident::#directive_ident(directive) => ::core::option::Option::Some(directive),
+                    #(#enum_ident::#signed_idents(signed) => signed.container_directive(),)*
+                    _ => ::core::option::Option::None
+                }
+            }
+        }
+
+        #(#conversions)*
+
+        impl #enum_ident {
+            /// Names of every known message type
+            pub const KINDS: &'static [&'static str] = &[#(#known_kinds),*];
+
+            /// Name of the message type, as written to the wire
+            pub fn kind(&self) -> &str {
+                match self {
+                    #(#enum_ident::#known_idents(_) => #known_kinds,)*
+                    #(#enum_ident::#unknown_idents(unknown) => &unknown.kind,)*
+                }
+            }
+
+            /// Whether this message is only accepted from within the container, never from another container
+            pub fn is_local_only(&self) -> bool {
+                #[allow(unreachable_patterns)]
+                match self {
+                    #(#enum_ident::#local_idents(_) => true,)*
+                    _ => false
+                }
+            }
+
+            /// Decodes the payload of a known message type
+            fn decode_payload(kind: &str, payload: ::serde_json::Value) -> ::core::result::Result<Self, ::std::string::String> {
+                match kind {
+                    #(#known_kinds => ::serde_json::from_value(payload)
+                        .map(#enum_ident::#known_idents)
+                        .map_err(|error| error.to_string()),)*
+                    _ => ::core::result::Result::Err(::std::format!("unknown message type `{}`", kind))
+                }
+            }
+
+            /// Writes the message's payload as the `payload` field of its wire representation
+            fn serialize_payload<S: ::serde::Serializer>(&self, wire: &mut S::SerializeStruct) -> ::core::result::Result<(), S::Error> {
+                use ::serde::ser::SerializeStruct;
+                match self {
+                    #(#enum_ident::#known_idents(message) => wire.serialize_field("payload", message),)*
+                    #(#enum_ident::#unknown_idents(unknown) => wire.serialize_field("payload", &unknown.payload),)*
+                }
+            }
+        }
+    })
+}
+
+/// Collects the variants of the enum, checking each wraps exactly one message type
+fn parse_variants(input: &DeriveInput) -> syn::Result<Vec<MessageVariant>> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "HyperionMessage can only be derived for enums"));
+    };
+
+    data.variants.iter().map(|variant| {
+        let ty = match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => fields.unnamed[0].ty.clone(),
+            _ => return Err(syn::Error::new_spanned(&variant.ident,
+                "HyperionMessage variants must wrap exactly one message type, e.g. `ExampleMessage(ExampleMessage)`"))
+        };
+
+        let mut role = VariantRole::Message;
+        for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("hyperion")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("directive") {
+                    role = VariantRole::Directive;
+                    Ok(())
+                } else if meta.path.is_ident("unknown") {
+                    role = VariantRole::Unknown;
+                    Ok(())
+                } else if meta.path.is_ident("local") {
+                    role = VariantRole::Local;
+                    Ok(())
+                } else if meta.path.is_ident("signed") {
+                    role = VariantRole::Signed;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `directive`, `unknown`, `local` or `signed`"))
+                }
+            })?;
+        }
+
+        Ok(MessageVariant { ident: variant.ident.clone(), ty, role })
+    }).collect()
+}